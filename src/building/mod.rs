@@ -0,0 +1,2 @@
+pub mod serializer;
+pub mod site_builder;
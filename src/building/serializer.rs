@@ -0,0 +1,435 @@
+use crate::{
+    generation::markdown::dedent,
+    parsing::{
+        link::Link, nav_entry::NavEntry, page::Page, site::Site, site_config::SiteConfig,
+        snippet::Snippet, variable::Variable,
+    },
+};
+
+const INDENT: &str = "    ";
+
+/// Writes a site back out as canonical `.dl` source: one field per line, four space
+/// indentation, and multi-line contents re-indented under their page.
+pub struct Serializer {
+    pub output: String,
+}
+
+impl Serializer {
+    pub fn new() -> Self {
+        Self {
+            output: String::new(),
+        }
+    }
+
+    pub fn serialize(mut self, site: &Site) -> String {
+        if !site.config.is_empty() {
+            self.serialize_config(&site.config);
+        }
+
+        if !site.variables.is_empty() {
+            if !self.output.is_empty() {
+                self.output.push('\n');
+            }
+
+            self.serialize_variables(&site.variables);
+        }
+
+        for snippet in &site.snippets {
+            if !self.output.is_empty() {
+                self.output.push('\n');
+            }
+
+            self.serialize_snippet(snippet);
+        }
+
+        match &site.nav {
+            // links and sections declared between the pages are written back in place
+            Some(nav) if site.declared_nav => self.serialize_declared(nav, &site.pages, 0),
+            Some(nav) => {
+                if !self.output.is_empty() {
+                    self.output.push('\n');
+                }
+
+                self.output.push_str("nav {\n");
+                self.serialize_nav_entries(nav, 1);
+                self.output.push_str("}\n");
+
+                for page in &site.pages {
+                    self.output.push('\n');
+                    self.serialize_page(page, 0);
+                }
+            }
+            None => {
+                for page in &site.pages {
+                    if !self.output.is_empty() {
+                        self.output.push('\n');
+                    }
+
+                    self.serialize_page(page, 0);
+                }
+            }
+        }
+
+        self.output
+    }
+
+    fn serialize_declared(&mut self, entries: &[NavEntry], pages: &[Page], level: usize) {
+        let indent = INDENT.repeat(level);
+
+        for entry in entries {
+            if !self.output.is_empty() && !self.output.ends_with("{\n") {
+                self.output.push('\n');
+            }
+
+            match entry {
+                NavEntry::Page { path, .. } => {
+                    if let Some(page) = pages.iter().find(|page| page.path == *path) {
+                        self.serialize_page(page, level);
+                    }
+                }
+                NavEntry::Section { name, entries, .. } => {
+                    self.output
+                        .push_str(&format!("{indent}section {} {{\n", quote(name)));
+                    self.serialize_declared(entries, pages, level + 1);
+                    self.output.push_str(&format!("{indent}}}\n"));
+                }
+                NavEntry::Link(link) => self.serialize_link(link, level),
+            }
+        }
+    }
+
+    fn serialize_config(&mut self, config: &SiteConfig) {
+        self.output.push_str("site {\n");
+
+        if let Some(templates) = &config.templates {
+            self.push_field(INDENT, "templates", templates);
+        }
+
+        if let Some(navigation_depth) = config.navigation_depth {
+            self.push_raw_field(INDENT, "navigation_depth", &navigation_depth.to_string());
+        }
+
+        if let Some(toc_min_level) = config.toc_min_level {
+            self.push_raw_field(INDENT, "toc_min_level", &toc_min_level.to_string());
+        }
+
+        if let Some(toc_max_level) = config.toc_max_level {
+            self.push_raw_field(INDENT, "toc_max_level", &toc_max_level.to_string());
+        }
+
+        if let Some(search) = config.search {
+            self.push_raw_field(INDENT, "search", &search.to_string());
+        }
+
+        if let Some(line_numbers) = config.line_numbers {
+            self.push_raw_field(INDENT, "line_numbers", &line_numbers.to_string());
+        }
+
+        if let Some(sort) = config.sort {
+            self.push_field(INDENT, "sort", sort.name());
+        }
+
+        if let Some(url) = &config.url {
+            self.push_field(INDENT, "url", url);
+        }
+
+        if let Some(description) = &config.description {
+            self.push_field(INDENT, "description", description);
+        }
+
+        if !config.keywords.is_empty() {
+            self.push_field(INDENT, "keywords", &config.keywords.join(", "));
+        }
+
+        if let Some(author) = &config.author {
+            self.push_field(INDENT, "author", author);
+        }
+
+        if let Some(image) = &config.image {
+            self.push_field(INDENT, "image", image);
+        }
+
+        if let Some(sitemap) = config.sitemap {
+            self.push_raw_field(INDENT, "sitemap", &sitemap.to_string());
+        }
+
+        if let Some(robots) = config.robots {
+            self.push_raw_field(INDENT, "robots", &robots.to_string());
+        }
+
+        if let Some(robots_txt) = &config.robots_txt {
+            self.push_block_field(INDENT, "robots", robots_txt);
+        }
+
+        if let Some(not_found) = config.not_found {
+            self.push_raw_field(INDENT, "not_found", &not_found.to_string());
+        }
+
+        if !config.markdown.is_empty() {
+            let field_indent = INDENT.repeat(2);
+
+            self.output.push_str(&format!("{INDENT}markdown {{\n"));
+
+            for (name, enabled) in config.markdown.settings() {
+                self.push_raw_field(&field_indent, name, &enabled.to_string());
+            }
+
+            self.output.push_str(&format!("{INDENT}}}\n"));
+        }
+
+        if !config.redirects.is_empty() {
+            let field_indent = INDENT.repeat(2);
+
+            self.output.push_str(&format!("{INDENT}redirects {{\n"));
+
+            for (name, enabled) in config.redirects.settings() {
+                self.push_raw_field(&field_indent, name, &enabled.to_string());
+            }
+
+            self.output.push_str(&format!("{INDENT}}}\n"));
+        }
+
+        if !config.palette.is_empty() {
+            let field_indent = INDENT.repeat(2);
+
+            self.output.push_str(&format!("{INDENT}palette {{\n"));
+
+            for (name, color) in &config.palette {
+                self.push_field(&field_indent, name, color);
+            }
+
+            self.output.push_str(&format!("{INDENT}}}\n"));
+        }
+
+        self.output.push_str("}\n");
+    }
+
+    fn serialize_variables(&mut self, variables: &[Variable]) {
+        self.output.push_str("vars {\n");
+
+        for variable in variables {
+            self.push_field(INDENT, &variable.name, &variable.value);
+        }
+
+        self.output.push_str("}\n");
+    }
+
+    fn serialize_nav_entries(&mut self, entries: &[NavEntry], level: usize) {
+        let indent = INDENT.repeat(level);
+
+        for entry in entries {
+            match entry {
+                NavEntry::Page { path, name, .. } => {
+                    self.output
+                        .push_str(&format!("{indent}page {}", quote(path)));
+
+                    match name {
+                        Some(name) => self
+                            .output
+                            .push_str(&format!(" {{ name: {} }}\n", quote(name))),
+                        None => self.output.push('\n'),
+                    }
+                }
+                NavEntry::Section { name, entries, .. } => {
+                    self.output
+                        .push_str(&format!("{indent}section {} {{\n", quote(name)));
+                    self.serialize_nav_entries(entries, level + 1);
+                    self.output.push_str(&format!("{indent}}}\n"));
+                }
+                NavEntry::Link(link) => self.serialize_link(link, level),
+            }
+        }
+    }
+
+    fn serialize_link(&mut self, link: &Link, level: usize) {
+        let indent = INDENT.repeat(level);
+        let field_indent = INDENT.repeat(level + 1);
+
+        self.output.push_str(&format!("{indent}link {{\n"));
+        self.push_field(&field_indent, "name", &link.name);
+        self.push_field(&field_indent, "url", &link.url);
+        self.output.push_str(&format!("{indent}}}\n"));
+    }
+
+    fn serialize_snippet(&mut self, snippet: &Snippet) {
+        self.output
+            .push_str(&format!("snippet {} {{\n", quote(&snippet.name)));
+        self.push_block_field(INDENT, "contents", &snippet.markdown_contents);
+        self.output.push_str("}\n");
+    }
+
+    fn serialize_page(&mut self, page: &Page, level: usize) {
+        let indent = INDENT.repeat(level);
+        let field_indent = INDENT.repeat(level + 1);
+
+        self.output.push_str(&format!("{indent}page {{\n"));
+        self.push_field(&field_indent, "name", &page.name);
+        self.push_field(&field_indent, "path", &page.path);
+
+        // pages that only list their subpages need no contents
+        if !(page.markdown_contents.is_empty() && page.index.is_some()) {
+            self.push_block_field(&field_indent, "contents", &page.markdown_contents);
+        }
+
+        if let Some(layout) = &page.layout {
+            self.push_field(&field_indent, "layout", layout);
+        }
+
+        if !page.toc {
+            self.push_raw_field(&field_indent, "toc", "false");
+        }
+
+        if let Some(order) = page.order {
+            self.push_raw_field(&field_indent, "order", &order.to_string());
+        }
+
+        if let Some(date) = &page.date {
+            self.push_field(&field_indent, "date", date);
+        }
+
+        if let Some(sort) = page.sort {
+            self.push_field(&field_indent, "sort", sort.name());
+        }
+
+        if let Some(index) = page.index {
+            self.push_field(&field_indent, "index", index.name());
+        }
+
+        if let Some(description) = &page.description {
+            self.push_field(&field_indent, "description", description);
+        }
+
+        if !page.keywords.is_empty() {
+            self.push_field(&field_indent, "keywords", &page.keywords.join(", "));
+        }
+
+        if let Some(author) = &page.author {
+            self.push_field(&field_indent, "author", author);
+        }
+
+        if let Some(image) = &page.image {
+            self.push_field(&field_indent, "image", image);
+        }
+
+        if let Some(canonical) = &page.canonical {
+            self.push_field(&field_indent, "canonical", canonical);
+        }
+
+        if !page.redirect_from.is_empty() {
+            self.push_field(
+                &field_indent,
+                "redirect_from",
+                &page.redirect_from.join(", "),
+            );
+        }
+
+        for state in page.states() {
+            self.push_raw_field(&field_indent, state, "true");
+        }
+
+        if let Some(condition) = &page.condition {
+            self.push_field(&field_indent, "when", condition);
+        }
+
+        for subpage in &page.subpages {
+            self.output.push('\n');
+            self.serialize_page(subpage, level + 1);
+        }
+
+        self.output.push_str(&format!("{indent}}}\n"));
+    }
+
+    fn push_field(&mut self, indent: &str, field: &str, value: &str) {
+        self.push_raw_field(indent, field, &quote(value));
+    }
+
+    /// Writes a field whose value is dedented when it's used, such as contents, as a block.
+    fn push_block_field(&mut self, indent: &str, field: &str, value: &str) {
+        self.push_raw_field(indent, field, &quote_block(value, indent));
+    }
+
+    /// Writes a field whose value is already in `.dl` syntax, such as a number.
+    fn push_raw_field(&mut self, indent: &str, field: &str, value: &str) {
+        self.output.push_str(&format!("{indent}{field}: {value}\n"));
+    }
+}
+
+/// Quotes a field value exactly, escaping backslashes and quotes. A literal `${` is written
+/// as `$${` so it isn't read back as a variable.
+pub fn quote(value: &str) -> String {
+    format!("\"{}\"", escape(value))
+}
+
+/// Quotes a value that is dedented when it's used, such as contents. Values that span
+/// several lines are written as a block, with each line indented to match the field, which
+/// the generator strips again on render. Any indentation beyond that, such as in code
+/// blocks, is kept.
+pub fn quote_block(value: &str, indent: &str) -> String {
+    let escaped = escape(value);
+
+    if !escaped.contains('\n') {
+        return format!("\"{escaped}\"");
+    }
+
+    let dedented = dedent(&escaped);
+    let lines = dedented.lines().collect::<Vec<_>>();
+    let first = lines.iter().position(|line| !line.trim().is_empty());
+    let last = lines.iter().rposition(|line| !line.trim().is_empty());
+    let mut quoted = String::from("\"\n");
+
+    if let (Some(first), Some(last)) = (first, last) {
+        for line in &lines[first..=last] {
+            if line.trim().is_empty() {
+                quoted.push('\n');
+            } else {
+                quoted.push_str(&format!("{indent}{line}\n"));
+            }
+        }
+    }
+
+    quoted.push_str(indent);
+    quoted.push('"');
+    quoted
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace("${", "$${")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_str, parsing::page::Page, parsing::site::Site};
+
+    #[test]
+    fn strings_survive_a_round_trip() {
+        let name = "A \"quoted\" name\nwith a backslash \\ and ${literal}";
+        let site = Site::new(vec![Page::new(
+            name,
+            "/",
+            "ends in a backslash\\",
+            Vec::new(),
+        )]);
+        let parsed = parse_str("test.dl", &site.to_dl()).unwrap();
+
+        assert_eq!(parsed.pages[0].name, name);
+        assert_eq!(parsed.pages[0].markdown_contents, "ends in a backslash\\");
+    }
+
+    #[test]
+    fn multi_line_contents_are_written_as_a_block() {
+        let site = Site::new(vec![Page::new(
+            "Home",
+            "/",
+            "\n# Home\n\n    code\n",
+            Vec::new(),
+        )]);
+
+        assert!(
+            site.to_dl()
+                .contains("    contents: \"\n    # Home\n\n        code\n    \"\n")
+        );
+    }
+}
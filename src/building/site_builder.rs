@@ -0,0 +1,120 @@
+use crate::{
+    errors::standard_error::StandardError,
+    parsing::{page::Page, site::Site, site_config::SiteConfig},
+    validation::validator::Validator,
+};
+
+/// Constructs a [`Site`] from Rust code instead of `.dl` source.
+///
+/// ```
+/// let site = dl::SiteBuilder::new()
+///     .page("Introduction", "/", "# DocsLegend")
+///     .child("Getting Started", "/getting-started", "# Getting Started")
+///     .page("Usage", "/usage", "# Using DocsLegend")
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(site.find("/getting-started").unwrap().name(), "Getting Started");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SiteBuilder {
+    config: SiteConfig,
+    pages: Vec<Page>,
+}
+
+impl SiteBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the directory of templates that override the built in ones.
+    pub fn templates(mut self, templates_dir: &str) -> Self {
+        self.config.templates = Some(templates_dir.to_string());
+
+        self
+    }
+
+    /// Adds a top level page.
+    pub fn page(mut self, name: &str, path: &str, contents: &str) -> Self {
+        self.pages.push(Page::new(name, path, contents, Vec::new()));
+
+        self
+    }
+
+    /// Adds a subpage to the most recently added top level page. Use
+    /// [`Page::with_subpage`] and [`SiteBuilder::push`] for deeper trees.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no top level page has been added yet.
+    pub fn child(mut self, name: &str, path: &str, contents: &str) -> Self {
+        let parent = self
+            .pages
+            .last_mut()
+            .expect("SiteBuilder::child called before any SiteBuilder::page");
+        parent
+            .subpages
+            .push(Page::new(name, path, contents, Vec::new()));
+
+        self
+    }
+
+    /// Adds an already constructed page, along with its subpages.
+    pub fn push(mut self, page: Page) -> Self {
+        self.pages.push(page);
+
+        self
+    }
+
+    /// Finishes the site, checking it with the same rules as a parsed one. The strings of
+    /// the pages are kept as given: there are no variables to resolve, so `${` is literal.
+    pub fn build(self) -> Result<Site, StandardError> {
+        let mut site = Site::new(self.pages);
+        site.config = self.config;
+
+        Validator::new().validate(&site)?;
+
+        Ok(site)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages_are_kept_as_given() {
+        let contents = "# Paths\n\n    indented code\n\nC:\\dir\\";
+        let site = SiteBuilder::new()
+            .page("First\nSecond", "/", contents)
+            .build()
+            .unwrap();
+
+        assert_eq!(site.pages[0].name, "First\nSecond");
+        assert_eq!(site.pages[0].markdown_contents, contents);
+    }
+
+    #[test]
+    fn dollar_braces_are_literal() {
+        let contents = "Set ${HOME} or $${HOME}";
+        let site = SiteBuilder::new()
+            .page("Home", "/", contents)
+            .build()
+            .unwrap();
+        let parsed = crate::parse_str("test.dl", &site.to_dl()).unwrap();
+
+        assert_eq!(site.pages[0].markdown_contents, contents);
+        assert_eq!(parsed.pages[0].markdown_contents, contents);
+    }
+
+    #[test]
+    fn pages_are_validated() {
+        let error = SiteBuilder::new()
+            .page("Home", "/", "x")
+            .child("Again", "/", "y")
+            .build()
+            .unwrap_err();
+
+        assert_eq!(error.text, "duplicate path '/' for page 'Again'");
+    }
+}
@@ -1 +1,2 @@
 pub mod standard_error;
+pub mod standard_warning;
@@ -1,9 +1,16 @@
 use crate::lexing::position::Position;
 use simply_colored::*;
-use std::fmt::Display;
+use std::{fmt::Display, ops::Deref};
 
+/// An error pointing at the code that caused it. The details are boxed so that results
+/// carrying one stay small.
 #[derive(Debug, Clone)]
 pub struct StandardError {
+    details: Box<ErrorDetails>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ErrorDetails {
     pub text: String,
     pub pos_start: Position,
     pub pos_end: Position,
@@ -13,14 +20,12 @@ pub struct StandardError {
 impl StandardError {
     pub fn new(text: &str, pos_start: Position, pos_end: Position, help: Option<&str>) -> Self {
         Self {
-            text: text.to_string(),
-            pos_start,
-            pos_end,
-            help: if help.is_some() {
-                Some(help.unwrap().to_string())
-            } else {
-                None
-            },
+            details: Box::new(ErrorDetails {
+                text: text.to_string(),
+                pos_start,
+                pos_end,
+                help: help.map(|help| help.to_string()),
+            }),
         }
     }
 
@@ -30,43 +35,56 @@ impl StandardError {
         pos_start: &Position,
         pos_end: &Position,
     ) -> String {
-        let lines: Vec<&str> = text.lines().collect();
-        let mut result = String::new();
-
-        for i in pos_start.line_num..=pos_end.line_num {
-            if let Some(line) = lines.get(i as usize) {
-                result.push_str("   | ");
-                result.push_str(line);
-                result.push('\n');
-
-                let col_start = if i == pos_start.line_num {
-                    pos_start.column_num as usize
-                } else {
-                    0
-                };
-
-                let col_end = if i == pos_end.line_num - 1 {
-                    pos_end.column_num as usize
-                } else {
-                    line.len()
-                };
-
-                let arrow_len = if col_end > col_start {
-                    col_end - col_start
-                } else {
-                    1
-                };
-
-                let arrow_line = " ".repeat(col_start) + &"^".repeat(arrow_len);
-                result.push_str(format!("   | {BOLD}{}{RESET}", &arrow_line).as_str());
-                result.push_str("\n   | ");
-            }
-        }
+        format_code_as_messup(text, pos_start, pos_end)
+    }
+}
+
+impl Deref for StandardError {
+    type Target = ErrorDetails;
 
-        result.replace('\t', "")
+    fn deref(&self) -> &ErrorDetails {
+        &self.details
     }
 }
 
+/// Prints the lines between two positions with '^' markers under the part they cover.
+pub fn format_code_as_messup(text: &str, pos_start: &Position, pos_end: &Position) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut result = String::new();
+
+    for i in pos_start.line_num..=pos_end.line_num {
+        if let Some(line) = lines.get(i as usize) {
+            result.push_str("   | ");
+            result.push_str(line);
+            result.push('\n');
+
+            let col_start = if i == pos_start.line_num {
+                pos_start.column_num as usize
+            } else {
+                0
+            };
+
+            let col_end = if i == pos_end.line_num - 1 {
+                pos_end.column_num as usize
+            } else {
+                line.len()
+            };
+
+            let arrow_len = if col_end > col_start {
+                col_end - col_start
+            } else {
+                1
+            };
+
+            let arrow_line = " ".repeat(col_start) + &"^".repeat(arrow_len);
+            result.push_str(format!("   | {BOLD}{}{RESET}", &arrow_line).as_str());
+            result.push_str("\n   | ");
+        }
+    }
+
+    result.replace('\t', "")
+}
+
 impl Display for StandardError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut output = String::new();
@@ -111,3 +129,50 @@ impl Display for StandardError {
         write!(f, "{output}{RESET}")
     }
 }
+
+/// Formats allowed values for a help message, e.g. `'name', 'path', and 'contents'`.
+pub fn list_options(options: &[&str]) -> String {
+    let quoted = options
+        .iter()
+        .map(|option| format!("'{option}'"))
+        .collect::<Vec<_>>();
+
+    match quoted.as_slice() {
+        [] => String::new(),
+        [only] => only.clone(),
+        [first, second] => format!("{first} and {second}"),
+        [rest @ .., last] => format!("{}, and {last}", rest.join(", ")),
+    }
+}
+
+/// The option closest to a misspelled `name`, if any is close enough to be a likely typo.
+pub fn closest_match<'a>(name: &str, options: &[&'a str]) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(2);
+
+    options
+        .iter()
+        .map(|option| (edit_distance(name, option), *option))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, option)| option)
+}
+
+/// The Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
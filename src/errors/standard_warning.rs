@@ -0,0 +1,52 @@
+use crate::{errors::standard_error::format_code_as_messup, lexing::position::Position};
+use simply_colored::*;
+use std::fmt::Display;
+
+/// Something that doesn't stop the site from being built but is probably a mistake.
+#[derive(Debug, Clone)]
+pub struct StandardWarning {
+    pub text: String,
+    pub pos_start: Position,
+    pub pos_end: Position,
+    pub help: Option<String>,
+}
+
+impl StandardWarning {
+    pub fn new(text: &str, pos_start: Position, pos_end: Position, help: Option<&str>) -> Self {
+        Self {
+            text: text.to_string(),
+            pos_start,
+            pos_end,
+            help: help.map(|help| help.to_string()),
+        }
+    }
+}
+
+impl Display for StandardWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut output = format!(
+            "{DIM_YELLOW}{BOLD}warning:{RESET} {}\n   in: {}:{}:{}",
+            self.text,
+            self.pos_start.filename,
+            self.pos_start.line_num + 1,
+            self.pos_start.column_num,
+        );
+
+        output.push_str(&format!(
+            "\n   + \n   | \n{}",
+            format_code_as_messup(
+                &self.pos_start.file_contents,
+                &self.pos_start,
+                &self.pos_end
+            )
+        ));
+
+        if let Some(msg) = &self.help {
+            output.push_str(&format!("\n   + - > {DIM_GREEN}{ITALIC}help:{RESET} {msg}"));
+        } else {
+            output.push_str("\n   + ");
+        }
+
+        write!(f, "{output}{RESET}")
+    }
+}
@@ -0,0 +1,257 @@
+use crate::generation::markdown::escape_html;
+
+/// The kinds of admonitions: their name in Markdown, the class and palette color they use,
+/// and their default title.
+const ADMONITION_KINDS: [(&str, &str, &str); 6] = [
+    ("note", "note", "Note"),
+    ("tip", "tip", "Tip"),
+    ("important", "important", "Important"),
+    ("warning", "warning", "Warning"),
+    ("caution", "caution", "Caution"),
+    ("danger", "caution", "Danger"),
+];
+
+/// Whether an admonition can be folded, and if so whether it starts open.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Fold {
+    None,
+    Open,
+    Closed,
+}
+
+/// The first line of an admonition, e.g. `> [!WARNING]- Breaking change` or `:::tip`.
+#[derive(Debug, Clone)]
+struct Admonition {
+    class: &'static str,
+    title: String,
+    fold: Fold,
+}
+
+impl Admonition {
+    /// Parses what follows `[!` in a blockquote or `:::` in a container: the kind, then an
+    /// optional `+` or `-` making it collapsible, then an optional title.
+    fn parse(header: &str, kind_end: char) -> Option<Self> {
+        let end = header
+            .find(|character: char| !character.is_ascii_alphabetic())
+            .unwrap_or(header.len());
+        let kind = header[..end].to_lowercase();
+        let (_, class, default_title) =
+            ADMONITION_KINDS.iter().find(|(name, _, _)| *name == kind)?;
+        let mut rest = &header[end..];
+
+        if kind_end != ' ' {
+            rest = rest.strip_prefix(kind_end)?;
+        }
+
+        let fold = match rest.chars().next() {
+            Some('+') => Fold::Open,
+            Some('-') => Fold::Closed,
+            _ => Fold::None,
+        };
+
+        if fold != Fold::None {
+            rest = &rest[1..];
+        }
+
+        let title = rest.trim();
+
+        Some(Self {
+            class,
+            title: if title.is_empty() {
+                default_title.to_string()
+            } else {
+                title.to_string()
+            },
+            fold,
+        })
+    }
+
+    /// Wraps the already expanded Markdown of the body. The HTML is separated from the body
+    /// by blank lines so that the body is still rendered as Markdown.
+    fn render(&self, body: &str) -> String {
+        let title = format!(
+            "{}<span>{}</span>",
+            admonition_icon(self.class),
+            escape_html(&self.title)
+        );
+        let class = format!("admonition admonition-{}", self.class);
+
+        match self.fold {
+            Fold::None => format!(
+                "<div class=\"{class}\" role=\"note\">\n<p class=\"admonition-title\">{title}</p>\n\n{body}\n\n</div>\n\n"
+            ),
+            Fold::Open | Fold::Closed => format!(
+                "<details class=\"{class}\"{}>\n<summary class=\"admonition-title\">{title}</summary>\n\n{body}\n\n</details>\n\n",
+                if self.fold == Fold::Open { " open" } else { "" }
+            ),
+        }
+    }
+}
+
+/// Turns `> [!NOTE]` blockquotes, and `:::note` containers when `containers` is on, into
+/// callout boxes before the Markdown is parsed.
+pub fn expand_admonitions(markdown: &str, containers: bool) -> String {
+    let lines = markdown.lines().collect::<Vec<_>>();
+    let mut output = String::new();
+    let mut fence: Option<&str> = None;
+    let mut index = 0;
+
+    while index < lines.len() {
+        let line = lines[index];
+        let trimmed = line.trim_start();
+        index += 1;
+
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+
+            output.push_str(line);
+            output.push('\n');
+            continue;
+        }
+
+        if let Some(marker) = ["```", "~~~"]
+            .into_iter()
+            .find(|marker| trimmed.starts_with(marker))
+        {
+            fence = Some(marker);
+            output.push_str(line);
+            output.push('\n');
+            continue;
+        }
+
+        let blockquote = trimmed
+            .strip_prefix('>')
+            .map(str::trim_start)
+            .and_then(|quoted| quoted.strip_prefix("[!"))
+            .and_then(|header| Admonition::parse(header, ']'));
+
+        if let Some(admonition) = blockquote {
+            let mut body = Vec::new();
+
+            while let Some(quoted) = lines
+                .get(index)
+                .and_then(|line| line.trim_start().strip_prefix('>'))
+            {
+                body.push(quoted.strip_prefix(' ').unwrap_or(quoted));
+                index += 1;
+            }
+
+            output.push_str(&admonition.render(&expand_admonitions(&body.join("\n"), containers)));
+            continue;
+        }
+
+        let container = trimmed
+            .strip_prefix(":::")
+            .filter(|_| containers)
+            .and_then(|header| Admonition::parse(header, ' '));
+
+        if let Some(admonition) = container {
+            let mut body = Vec::new();
+            let mut depth = 1;
+
+            while let Some(line) = lines.get(index) {
+                let trimmed = line.trim();
+                index += 1;
+
+                if trimmed == ":::" {
+                    depth -= 1;
+
+                    if depth == 0 {
+                        break;
+                    }
+                } else if trimmed.starts_with(":::") {
+                    depth += 1;
+                }
+
+                body.push(*line);
+            }
+
+            output.push_str(&admonition.render(&expand_admonitions(&body.join("\n"), containers)));
+            continue;
+        }
+
+        output.push_str(line);
+        output.push('\n');
+    }
+
+    output
+}
+
+fn admonition_icon(class: &str) -> String {
+    let shapes = match class {
+        "tip" => r#"<path d="M5.5 10.5a4.5 4.5 0 1 1 5 0V12h-5z"/><path d="M6 14.5h4"/>"#,
+        "important" => {
+            r#"<path d="M1.5 2.5h13v9h-7l-3 3v-3h-3z"/><path d="M8 4.5v3.5"/><circle cx="8" cy="10" r=".5"/>"#
+        }
+        "warning" => {
+            r#"<path d="M8 1.5l7 12.5H1z"/><path d="M8 6v4"/><circle cx="8" cy="12" r=".5"/>"#
+        }
+        "caution" => {
+            r#"<path d="M5 1.5h6L14.5 5v6L11 14.5H5L1.5 11V5z"/><path d="M8 4.5v4"/><circle cx="8" cy="11" r=".5"/>"#
+        }
+        _ => {
+            r#"<circle cx="8" cy="8" r="6.5"/><path d="M8 7v4.5"/><circle cx="8" cy="4.75" r=".5"/>"#
+        }
+    };
+
+    format!(
+        "<svg class=\"admonition-icon\" aria-hidden=\"true\" viewBox=\"0 0 16 16\" width=\"16\" height=\"16\" fill=\"none\" stroke=\"currentColor\" stroke-width=\"1.5\" stroke-linejoin=\"round\">{shapes}</svg>"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand_admonitions;
+
+    #[test]
+    fn blockquotes_become_callouts() {
+        let html = expand_admonitions("> [!WARNING]\n> Back up *first*.\n\nAfter", false);
+
+        assert!(html.starts_with("<div class=\"admonition admonition-warning\" role=\"note\">"));
+        assert!(html.contains("<span>Warning</span></p>\n\nBack up *first*.\n"));
+        assert!(html.contains("</div>\n"));
+        assert!(html.ends_with("After\n"));
+    }
+
+    #[test]
+    fn titles_and_folding_are_optional() {
+        let html = expand_admonitions("> [!tip]- Faster <builds>\n> Cache them.", false);
+
+        assert!(html.starts_with("<details class=\"admonition admonition-tip\">"));
+        assert!(html.contains("<span>Faster &lt;builds&gt;</span></summary>"));
+
+        let html = expand_admonitions("> [!NOTE]+\n> Text", false);
+        assert!(html.starts_with("<details class=\"admonition admonition-note\" open>"));
+
+        let html = expand_admonitions("> [!DANGER]\n> Text", false);
+        assert!(html.contains("admonition-caution"));
+        assert!(html.contains("<span>Danger</span>"));
+    }
+
+    #[test]
+    fn unknown_kinds_stay_blockquotes() {
+        let markdown = "> [!UNKNOWN]\n> Text\n";
+
+        assert_eq!(expand_admonitions(markdown, true), markdown);
+    }
+
+    #[test]
+    fn containers_nest_and_need_the_extension() {
+        let markdown = ":::note Outer\nOne\n:::tip\nTwo\n:::\nThree\n:::\n";
+        let html = expand_admonitions(markdown, true);
+
+        assert_eq!(html.matches("<div class=\"admonition").count(), 2);
+        assert!(html.contains("<span>Outer</span>"));
+        assert!(html.trim_end().ends_with("Three\n\n\n</div>"));
+        assert_eq!(expand_admonitions(markdown, false), markdown);
+    }
+
+    #[test]
+    fn code_blocks_are_left_alone() {
+        let markdown = "```\n> [!NOTE]\n:::note\n```\n";
+
+        assert_eq!(expand_admonitions(markdown, true), markdown);
+    }
+}
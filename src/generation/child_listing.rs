@@ -0,0 +1,121 @@
+use crate::{
+    generation::{generator::page_href, markdown::escape_html},
+    parsing::{index_style::IndexStyle, page::Page},
+};
+use serde::Serialize;
+
+/// What a listing shows of one subpage.
+#[derive(Debug, Clone)]
+pub struct ChildSummary {
+    pub title: String,
+    pub href: String,
+    /// The description of the page, or its first paragraph.
+    pub description: String,
+}
+
+/// A subpage as layouts see it, in the `children` of a page.
+#[derive(Debug, Serialize)]
+pub struct ChildPage<'a> {
+    pub title: &'a str,
+    pub href: String,
+    pub date: Option<&'a str>,
+    pub description: Option<&'a str>,
+}
+
+impl<'a> ChildPage<'a> {
+    pub fn new(page: &'a Page) -> Self {
+        Self {
+            title: &page.name,
+            href: page_href(&page.path),
+            date: page.date.as_deref(),
+            description: page.description.as_deref(),
+        }
+    }
+}
+
+/// The HTML of a listing of `children`, as a block that can go in the middle of Markdown.
+/// It has no blank lines, which would end the HTML block early. Without children there's
+/// nothing to list, so it's empty.
+pub fn render_listing(children: &[ChildSummary], style: IndexStyle) -> String {
+    let mut html = String::new();
+
+    if children.is_empty() {
+        return html;
+    }
+
+    match style {
+        IndexStyle::List => {
+            html.push_str("<ul class=\"child-pages child-pages-list\">\n");
+
+            for child in children {
+                html.push_str(&format!(
+                    "<li><a href=\"{}\">{}</a>",
+                    escape_html(&child.href),
+                    escape_html(&child.title)
+                ));
+
+                if !child.description.is_empty() {
+                    html.push_str(&format!("<p>{}</p>", escape_html(&child.description)));
+                }
+
+                html.push_str("</li>\n");
+            }
+
+            html.push_str("</ul>\n\n");
+        }
+        IndexStyle::Cards => {
+            html.push_str("<div class=\"child-pages child-pages-cards\">\n");
+
+            for child in children {
+                html.push_str(&format!(
+                    "<a class=\"child-page-card\" href=\"{}\"><strong>{}</strong>",
+                    escape_html(&child.href),
+                    escape_html(&child.title)
+                ));
+
+                if !child.description.is_empty() {
+                    html.push_str(&format!("<span>{}</span>", escape_html(&child.description)));
+                }
+
+                html.push_str("</a>\n");
+            }
+
+            html.push_str("</div>\n\n");
+        }
+    }
+
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChildSummary, render_listing};
+    use crate::parsing::index_style::IndexStyle;
+
+    fn child(title: &str, description: &str) -> ChildSummary {
+        ChildSummary {
+            title: title.to_string(),
+            href: "/guides/install/".to_string(),
+            description: description.to_string(),
+        }
+    }
+
+    #[test]
+    fn nothing_is_listed_without_children() {
+        assert_eq!(render_listing(&[], IndexStyle::List), "");
+        assert_eq!(render_listing(&[], IndexStyle::Cards), "");
+    }
+
+    #[test]
+    fn listings_escape_and_skip_empty_descriptions() {
+        let children = [child("<Install>", "Set up & go"), child("Deploy", "")];
+        let list = render_listing(&children, IndexStyle::List);
+        let cards = render_listing(&children, IndexStyle::Cards);
+
+        assert!(list.contains("&lt;Install&gt;</a><p>Set up &amp; go</p></li>"));
+        assert!(list.contains(">Deploy</a></li>"));
+        assert!(cards.contains("<strong>Deploy</strong></a>"));
+        assert!(!list.trim_end().contains("\n\n"));
+        assert!(!cards.trim_end().contains("\n\n"));
+    }
+}
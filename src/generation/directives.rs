@@ -0,0 +1,397 @@
+use crate::{
+    errors::standard_error::{StandardError, list_options},
+    generation::{
+        child_listing::{ChildSummary, render_listing},
+        includes::include,
+        markdown::dedent,
+    },
+    lexing::position::Position,
+    parsing::{
+        index_style::{INDEX_STYLES, IndexStyle},
+        page::Page,
+        site::Site,
+        snippet::{Snippet, is_parameter_name},
+    },
+};
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::PathBuf,
+};
+
+const DIRECTIVE_START: &str = "{{#";
+const DIRECTIVE_END: &str = "}}";
+
+/// The name of a referenced snippet and the `key=value` arguments passed to it.
+type SnippetReference<'a> = (&'a str, Vec<(&'a str, String)>);
+
+/// Expands the `{{#include ...}}`, `{{#snippet ...}}` and `{{#children}}` directives of
+/// page contents, in one pass over its lines as written, so that errors can point at the
+/// directive that caused them.
+pub struct Directives {
+    /// The directory of the `.dl` file, which included files are relative to.
+    pub source_dir: PathBuf,
+    pub snippets: BTreeMap<String, Snippet>,
+    /// The snippets referenced so far, to warn about the others once every page is done.
+    pub used_snippets: HashSet<String>,
+    /// The subpages of the page being expanded, which `{{#children}}` lists.
+    pub children: Vec<ChildSummary>,
+}
+
+impl Directives {
+    pub fn new(site: &Site) -> Self {
+        Self {
+            source_dir: site.resolve_path(""),
+            snippets: site
+                .snippets
+                .iter()
+                .map(|snippet| (snippet.name.clone(), snippet.clone()))
+                .collect(),
+            used_snippets: HashSet::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// The contents of the page with every directive replaced.
+    pub fn expand_page(&mut self, page: &Page) -> Result<String, StandardError> {
+        let markdown = dedent(&page.markdown_contents);
+
+        if !markdown.contains(DIRECTIVE_START) {
+            return Ok(page.markdown_contents.clone());
+        }
+
+        self.expand(
+            &markdown,
+            &|line, text| page.contents_range(line, text),
+            &mut Vec::new(),
+        )
+    }
+
+    /// Whether the page lists its subpages with `{{#children}}`, itself or through a snippet.
+    pub fn lists_children(&self, page: &Page) -> bool {
+        let directive = format!("{DIRECTIVE_START}children");
+
+        page.markdown_contents.contains(&directive)
+            || self
+                .snippets
+                .values()
+                .any(|snippet| snippet.markdown_contents.contains(&directive))
+    }
+
+    /// The snippets that no page referenced, directly or through another snippet.
+    pub fn unused_snippets(&self) -> impl Iterator<Item = &Snippet> {
+        self.snippets
+            .values()
+            .filter(|snippet| !self.used_snippets.contains(&snippet.name))
+    }
+
+    /// `stack` holds the snippets being expanded, to catch snippets that include themselves.
+    fn expand(
+        &mut self,
+        markdown: &str,
+        locate: &dyn Fn(usize, &str) -> (Position, Position),
+        stack: &mut Vec<String>,
+    ) -> Result<String, StandardError> {
+        let mut lines = Vec::new();
+
+        for (line_index, line) in markdown.lines().enumerate() {
+            let mut expanded = String::new();
+            let mut rest = line;
+
+            while let Some(start) = rest.find(DIRECTIVE_START) {
+                let Some(length) = rest[start..].find(DIRECTIVE_END) else {
+                    break;
+                };
+                let directive = &rest[start..start + length + DIRECTIVE_END.len()];
+                let inner =
+                    &directive[DIRECTIVE_START.len()..directive.len() - DIRECTIVE_END.len()];
+                let (name, argument) = inner.split_once(' ').unwrap_or((inner, ""));
+                let range = || locate(line_index, directive);
+
+                expanded.push_str(&rest[..start]);
+                rest = &rest[start + directive.len()..];
+
+                match name {
+                    "include" => {
+                        let included = include(argument.trim(), &self.source_dir).map_err(
+                            |(text, help)| {
+                                let (pos_start, pos_end) = range();
+
+                                StandardError::new(&text, pos_start, pos_end, help.as_deref())
+                            },
+                        )?;
+
+                        expanded.push_str(&included);
+                    }
+                    "snippet" => {
+                        expanded.push_str(&self.expand_snippet(argument, range(), stack)?)
+                    }
+                    "children" => {
+                        let style = match argument.trim() {
+                            "" => IndexStyle::default(),
+                            name => IndexStyle::from_name(name).ok_or_else(|| {
+                                let (pos_start, pos_end) = range();
+
+                                StandardError::new(
+                                    &format!("unknown index style '{name}'"),
+                                    pos_start,
+                                    pos_end,
+                                    Some(&format!(
+                                        "index styles are {}",
+                                        list_options(&INDEX_STYLES)
+                                    )),
+                                )
+                            })?,
+                        };
+
+                        // the listing is a block of HTML, which has to start on its own line
+                        expanded.push('\n');
+                        expanded.push_str(&render_listing(&self.children, style));
+                    }
+                    // not ours, e.g. a template example in a code block
+                    _ => expanded.push_str(directive),
+                }
+            }
+
+            expanded.push_str(rest);
+            lines.push(expanded);
+        }
+
+        Ok(lines.join("\n"))
+    }
+
+    fn expand_snippet(
+        &mut self,
+        argument: &str,
+        range: (Position, Position),
+        stack: &mut Vec<String>,
+    ) -> Result<String, StandardError> {
+        let (pos_start, pos_end) = range;
+        let error = |text: &str, help: &str| {
+            StandardError::new(text, pos_start.clone(), pos_end.clone(), Some(help))
+        };
+        let (name, arguments) =
+            parse_reference(argument).map_err(|(text, help)| error(&text, &help))?;
+
+        let Some(snippet) = self.snippets.get(name).cloned() else {
+            let names = self.snippets.keys().map(String::as_str).collect::<Vec<_>>();
+            let help = if names.is_empty() {
+                "declare it with snippet \"name\" { contents: \"...\" }".to_string()
+            } else {
+                format!("snippets are {}", list_options(&names))
+            };
+
+            return Err(error(&format!("unknown snippet '{name}'"), &help));
+        };
+
+        if stack.iter().any(|included| included == name) {
+            let chain = stack
+                .iter()
+                .map(String::as_str)
+                .chain([name])
+                .collect::<Vec<_>>()
+                .join("' -> '");
+
+            return Err(error(
+                &format!("snippet '{name}' includes itself"),
+                &format!("it is included through '{chain}'"),
+            ));
+        }
+
+        self.used_snippets.insert(name.to_string());
+
+        let parameters = snippet.parameters();
+        let parameters_help = if parameters.is_empty() {
+            format!("'{name}' takes no parameters")
+        } else {
+            format!(
+                "the parameters of '{name}' are {}",
+                list_options(&parameters)
+            )
+        };
+
+        for (key, _) in &arguments {
+            if !parameters.contains(key) {
+                return Err(error(
+                    &format!("snippet '{name}' has no parameter '{key}'"),
+                    &parameters_help,
+                ));
+            }
+        }
+
+        for parameter in &parameters {
+            if !arguments.iter().any(|(key, _)| key == parameter) {
+                return Err(error(
+                    &format!("missing parameter '{parameter}' of snippet '{name}'"),
+                    &format!("pass it as {{{{#snippet {name} {parameter}=value}}}}"),
+                ));
+            }
+        }
+
+        let mut markdown = dedent(&snippet.markdown_contents);
+
+        for (key, value) in &arguments {
+            markdown = markdown.replace(&format!("{{{{{key}}}}}"), value);
+        }
+
+        stack.push(name.to_string());
+
+        let expanded = self.expand(
+            &markdown,
+            &|line, text| snippet.contents_range(line, text),
+            stack,
+        )?;
+
+        stack.pop();
+
+        Ok(expanded.trim_matches('\n').to_string())
+    }
+}
+
+/// Splits `name key=value key="quoted value"` into the name and its arguments.
+fn parse_reference(argument: &str) -> Result<SnippetReference<'_>, (String, String)> {
+    let argument = argument.trim();
+    let (name, mut rest) = argument
+        .split_once(char::is_whitespace)
+        .unwrap_or((argument, ""));
+    let mut arguments = Vec::new();
+
+    if name.is_empty() {
+        return Err((
+            "missing snippet name".to_string(),
+            "write it as {{#snippet name}}".to_string(),
+        ));
+    }
+
+    loop {
+        rest = rest.trim_start();
+
+        if rest.is_empty() {
+            break;
+        }
+
+        let Some((key, value)) = rest
+            .split_once('=')
+            .filter(|(key, _)| is_parameter_name(key))
+        else {
+            return Err((
+                format!("invalid argument '{rest}' for snippet '{name}'"),
+                "pass parameters as key=value or key=\"a value with spaces\"".to_string(),
+            ));
+        };
+
+        let (value, remaining) = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => match value[1..].find(quote) {
+                Some(end) => (&value[1..end + 1], &value[end + 2..]),
+                None => {
+                    return Err((
+                        format!("unfinished value for '{key}'"),
+                        format!("add a {quote} at the end of the value to close it"),
+                    ));
+                }
+            },
+            _ => value.split_once(char::is_whitespace).unwrap_or((value, "")),
+        };
+
+        arguments.push((key, value.to_string()));
+        rest = remaining;
+    }
+
+    Ok((name, arguments))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Directives;
+    use crate::{errors::standard_error::StandardError, parse_str};
+
+    const SNIPPETS: &str = r#"
+        snippet "install" { contents: "Install version {{version}} of {{name}}." }
+        snippet "note" { contents: "> {{#snippet install version=1 name='dl tools'}}" }
+        snippet "loop" { contents: "{{#snippet again}}" }
+        snippet "again" { contents: "{{#snippet loop}}" }
+        snippet "unused" { contents: "Never referenced." }
+    "#;
+
+    /// Expands contents of a page of a site declaring [`SNIPPETS`], returning the
+    /// directives too, for what they learned along the way.
+    fn expand(contents: &str) -> (Directives, Result<String, StandardError>) {
+        let code =
+            format!("{SNIPPETS}\npage {{ name: \"Home\" path: \"/\" contents: \"{contents}\" }}");
+        let site = parse_str("test.dl", &code).unwrap();
+        let mut directives = Directives::new(&site);
+        let expanded = directives.expand_page(&site.pages[0]);
+
+        (directives, expanded)
+    }
+
+    #[test]
+    fn snippets_are_filled_in_with_their_arguments() {
+        let (_, expanded) = expand("{{#snippet install version=2.3.1 name=\\\"the CLI\\\"}}");
+
+        assert_eq!(expanded.unwrap(), "Install version 2.3.1 of the CLI.");
+
+        let (_, expanded) = expand("{{#snippet note}}");
+
+        assert_eq!(expanded.unwrap(), "> Install version 1 of dl tools.");
+    }
+
+    #[test]
+    fn wrong_arguments_are_errors() {
+        let (_, expanded) = expand("{{#snippet install version=1}}");
+
+        assert_eq!(
+            expanded.unwrap_err().text,
+            "missing parameter 'name' of snippet 'install'"
+        );
+
+        let (_, expanded) = expand("{{#snippet install version=1 name=x size=2}}");
+        let error = expanded.unwrap_err();
+
+        assert_eq!(error.text, "snippet 'install' has no parameter 'size'");
+        assert_eq!(
+            error.help.as_deref(),
+            Some("the parameters of 'install' are 'version' and 'name'")
+        );
+
+        let (_, expanded) = expand("{{#snippet install version='1}}");
+
+        assert_eq!(expanded.unwrap_err().text, "unfinished value for 'version'");
+    }
+
+    #[test]
+    fn unknown_snippets_are_errors() {
+        let (_, expanded) = expand("{{#snippet instal}}");
+
+        assert_eq!(expanded.unwrap_err().text, "unknown snippet 'instal'");
+    }
+
+    #[test]
+    fn recursive_snippets_are_errors() {
+        let (_, expanded) = expand("{{#snippet loop}}");
+        let error = expanded.unwrap_err();
+
+        assert_eq!(error.text, "snippet 'loop' includes itself");
+        assert_eq!(
+            error.help.as_deref(),
+            Some("it is included through 'loop' -> 'again' -> 'loop'")
+        );
+    }
+
+    #[test]
+    fn snippets_used_through_others_count_as_used() {
+        let (directives, _) = expand("{{#snippet note}}");
+        let unused = directives
+            .unused_snippets()
+            .map(|snippet| snippet.name.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(unused, ["again", "loop", "unused"]);
+    }
+
+    #[test]
+    fn other_directives_are_kept() {
+        let (_, expanded) = expand("{{#raw}} and {{name}}");
+
+        assert_eq!(expanded.unwrap(), "{{#raw}} and {{name}}");
+    }
+}
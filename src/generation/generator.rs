@@ -1,90 +1,866 @@
-use crate::parsing::page::Page;
-use std::{fs, path::PathBuf};
+use crate::{
+    errors::{
+        standard_error::{StandardError, closest_match, list_options},
+        standard_warning::StandardWarning,
+    },
+    generation::{
+        child_listing::{ChildPage, ChildSummary, render_listing},
+        directives::Directives,
+        headings::{Heading, table_of_contents},
+        highlighting::find_invalid_line_ranges,
+        json_output::{PageDocument, SiteManifest},
+        markdown::{
+            MarkdownOptions, RenderedMarkdown, dedent, find_disabled_syntax, render_markdown,
+        },
+        navigation::{Navigation, listed},
+        outline::{Outline, PageTrail},
+        page_meta::{MAX_DESCRIPTION_LENGTH, PageMeta},
+        palette::Palette,
+        render_options::{OutputFormat, RenderOptions},
+        search::SearchIndex,
+        sitemap::Sitemap,
+        tabs::find_stray_tab_lines,
+        templates::{DEFAULT_LAYOUT, Templates},
+    },
+    lexing::position::Position,
+    parsing::{nav_entry::NavEntry, page::Page, site::Site, site_config::SiteConfig},
+};
+use minijinja::{Value, context};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The path of the page served for paths no page is at. A page declared at it replaces the
+/// built in one.
+pub const NOT_FOUND_PATH: &str = "/404";
+
+/// The contents of the built in not found page.
+const NOT_FOUND_CONTENTS: &str = "# Page not found
+
+There's no page at this address. It may have moved, or the link to it may be broken. Try \
+searching for it, or start again from the [home page](/).";
 
 pub struct Generator {
     pub output_dir: PathBuf,
-    pub navigation_html: String,
+    pub directives: Directives,
+    pub formats: Vec<OutputFormat>,
+    pub templates: Templates,
+    pub navigation: Navigation,
+    pub navigation_depth: Option<usize>,
+    pub toc_levels: (u8, u8),
+    pub markdown_options: MarkdownOptions,
+    /// For the defaults of the metadata of pages.
+    pub config: SiteConfig,
+    /// Filled in while pages are generated, `None` when search is turned off.
+    pub search_index: Option<SearchIndex>,
+    /// The paths of every page being built, to check links against.
+    pub page_paths: HashSet<String>,
+    /// The old paths of pages, trimmed like `page_paths`, with the path each redirects to.
+    pub redirects: BTreeMap<String, String>,
+    /// The paths of the pages the active profiles left out, with why each was.
+    pub excluded_pages: BTreeMap<String, String>,
+    /// Whether pages are marked with their states, such as draft, when rendered.
+    pub preview: bool,
+    pub warnings: Vec<StandardWarning>,
 }
 
 impl Generator {
-    pub fn new(output_dir: &str) -> Self {
-        let output_dir = PathBuf::from(output_dir.to_owned());
+    pub fn new(options: &RenderOptions, site: &Site) -> Self {
+        let output_dir = options.output_dir.clone();
+        let templates_dir = options.templates_dir.clone().or_else(|| {
+            site.config
+                .templates
+                .as_ref()
+                .map(|templates| site.resolve_path(templates))
+        });
 
-        let generator = Self {
+        Self {
             output_dir: output_dir.to_owned(),
-            navigation_html: String::new(),
-        };
+            directives: Directives::new(site),
+            formats: options.formats.clone(),
+            templates: Templates::new(templates_dir),
+            navigation: Navigation::new(site),
+            navigation_depth: site.config.navigation_depth,
+            toc_levels: site.config.toc_levels(),
+            markdown_options: MarkdownOptions {
+                line_numbers: site.config.line_numbers(),
+                extensions: site.config.markdown.clone(),
+            },
+            config: site.config.clone(),
+            search_index: (options.formats.contains(&OutputFormat::Html) && site.config.search())
+                .then(SearchIndex::new),
+            page_paths: site
+                .iter()
+                .map(|page| page.path.trim_end_matches('/').to_string())
+                .collect(),
+            redirects: site
+                .iter()
+                .flat_map(|page| {
+                    page.redirect_from.iter().map(|old_path| {
+                        (
+                            old_path.trim_end_matches('/').to_string(),
+                            page.path.clone(),
+                        )
+                    })
+                })
+                .collect(),
+            excluded_pages: BTreeMap::new(),
+            preview: options.drafts,
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn render_site(&mut self, site: &Site) -> Result<(), StandardError> {
+        if self.formats.contains(&OutputFormat::Html) {
+            self.check_layouts(site)?;
+        }
+
+        self.write_assets(site)?;
+
+        let outline = Outline::new(site);
+
+        self.check_nav(site, &outline);
+
+        if let Some(description) = &site.config.description
+            && let Some(range) = &site.config.description_range
+        {
+            self.check_description(description, range.clone());
+        }
+
+        self.generate(&site.pages, None, &outline)?;
+        self.check_unused_snippets();
+
+        if self.formats.contains(&OutputFormat::Html) {
+            self.write_site_files(site, &outline)?;
+            self.write_redirects(site)?;
+        }
+
+        if let Some(search_index) = self.search_index.take() {
+            write_output(
+                &self.output_dir.join("search-index.json"),
+                search_index.into_json(),
+            )?;
+        }
+
+        if self.formats.contains(&OutputFormat::Json) {
+            let manifest = serde_json::to_string_pretty(&SiteManifest::new(site))
+                .expect("Error serializing site.json");
+
+            write_output(&self.output_dir.join("site.json"), manifest)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn generate(
+        &mut self,
+        pages: &[Page],
+        parent: Option<&Page>,
+        outline: &Outline,
+    ) -> Result<(), StandardError> {
+        for page in pages {
+            let page_dir = self.output_dir.join(page.path.trim_start_matches('/'));
+            create_output_dir(&page_dir)?;
+
+            self.check_disabled_syntax(page);
+            self.check_stray_tab_lines(page);
+            self.check_line_ranges(page);
+
+            if let Some(description) = &page.description {
+                let range = page
+                    .description_range
+                    .clone()
+                    .unwrap_or_else(|| page.keyword_range());
+
+                self.check_description(description, range);
+            }
+
+            self.directives.children = self.child_summaries(page)?;
+
+            let mut contents = dedent(&self.directives.expand_page(page)?);
+
+            if let Some(style) = page.index
+                && !self.directives.children.is_empty()
+            {
+                contents.push_str("\n\n");
+                contents.push_str(&render_listing(&self.directives.children, style));
+            }
+
+            let rendered = render_markdown(&contents, &self.markdown_options);
+
+            self.check_links(page, &rendered);
+
+            if let Some(search_index) = self.search_index.as_mut()
+                && !outline.hidden(&page.path)
+            {
+                search_index.add_page(page, page_href(&page.path), &rendered);
+            }
+
+            if self.formats.contains(&OutputFormat::Html) {
+                let html = self.render_page(page, &rendered, outline.trail(&page.path))?;
+
+                if self.config.not_found() && page.path.trim_end_matches('/') == NOT_FOUND_PATH {
+                    write_output(&self.output_dir.join("404.html"), &html)?;
+                }
+
+                write_output(&page_dir.join("index.html"), html)?;
+            }
+
+            if self.formats.contains(&OutputFormat::Json) {
+                let toc = self.page_toc(page, &rendered);
+                let document = PageDocument::new(page, &rendered, toc, parent, pages);
+                let json =
+                    serde_json::to_string_pretty(&document).expect("Error serializing page json");
+
+                write_output(&page_dir.join("index.json"), json)?;
+            }
+
+            self.generate(&page.subpages, Some(page), outline)?;
+        }
+
+        Ok(())
+    }
+
+    /// What listings of the subpages of the page show of each, if the page has any. Each
+    /// subpage is described by its description, or the first paragraph of its contents.
+    fn child_summaries(&mut self, page: &Page) -> Result<Vec<ChildSummary>, StandardError> {
+        if page.index.is_none() && !self.directives.lists_children(page) {
+            return Ok(Vec::new());
+        }
+
+        self.directives.children = Vec::new();
+
+        listed(&page.subpages)
+            .map(|child| {
+                let description = match &child.description {
+                    Some(description) => description.clone(),
+                    None => {
+                        let contents = self.directives.expand_page(child)?;
+
+                        render_markdown(&contents, &self.markdown_options).excerpt
+                    }
+                };
+
+                Ok(ChildSummary {
+                    title: child.name.clone(),
+                    href: page_href(&child.path),
+                    description,
+                })
+            })
+            .collect()
+    }
+
+    /// Creates the output directory, with the stylesheet and scripts the pages load.
+    fn write_assets(&self, site: &Site) -> Result<(), StandardError> {
+        create_output_dir(&self.output_dir)?;
+
+        if self.formats.contains(&OutputFormat::Html) {
+            write_output(
+                &self.output_dir.join("style.css"),
+                Palette::new(&site.config.palette).apply(include_str!("../../templates/style.css")),
+            )?;
+            write_output(
+                &self.output_dir.join("tabs.js"),
+                include_str!("../../templates/tabs.js"),
+            )?;
+        }
+
+        if self.search_index.is_some() {
+            write_output(
+                &self.output_dir.join("search.js"),
+                include_str!("../../templates/search.js"),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes the files a site is served with besides its pages: `sitemap.xml`, `robots.txt`
+    /// and, unless a page replaces it, `404.html`.
+    fn write_site_files(&mut self, site: &Site, outline: &Outline) -> Result<(), StandardError> {
+        let config = &site.config;
+        let mut sitemap_url = None;
+
+        if config.sitemap() {
+            match &config.url {
+                Some(url) => {
+                    write_output(
+                        &self.output_dir.join("sitemap.xml"),
+                        Sitemap::new(outline, url).into_xml(),
+                    )?;
+
+                    sitemap_url = Some(format!("{}/sitemap.xml", url.trim_end_matches('/')));
+                }
+                // only worth pointing out when it was asked for
+                None if config.sitemap.is_some() => {
+                    if let Some(pos_start) = &config.pos_start {
+                        let mut pos_end = pos_start.clone();
+
+                        for _ in 0.."site".len() {
+                            pos_end.advance(None);
+                        }
+
+                        self.warnings.push(StandardWarning::new(
+                            "sitemap.xml can't be generated without the url of the site",
+                            pos_start.clone(),
+                            pos_end,
+                            Some("set it with url: \"https://...\" in the site block"),
+                        ));
+                    }
+                }
+                None => {}
+            }
+        }
+
+        if config.robots() {
+            let robots = match &config.robots_txt {
+                Some(robots_txt) => format!("{}\n", dedent(robots_txt).trim()),
+                None => {
+                    let mut robots = String::from("User-agent: *\nAllow: /\n");
+
+                    if let Some(sitemap_url) = &sitemap_url {
+                        robots.push_str(&format!("\nSitemap: {sitemap_url}\n"));
+                    }
+
+                    robots
+                }
+            };
 
-        let _ = fs::create_dir_all(&output_dir);
-        let _ = fs::write(
-            &output_dir.join("style.css"),
-            include_str!("../../templates/style.css"),
+            write_output(&self.output_dir.join("robots.txt"), robots)?;
+        }
+
+        if config.not_found() && !self.page_paths.contains(NOT_FOUND_PATH) {
+            let page = Page::new(
+                "Page not found",
+                NOT_FOUND_PATH,
+                NOT_FOUND_CONTENTS,
+                Vec::new(),
+            )
+            .with_toc(false);
+            let rendered = render_markdown(NOT_FOUND_CONTENTS, &self.markdown_options);
+
+            write_output(
+                &self.output_dir.join("404.html"),
+                self.render_page(&page, &rendered, outline.trail(NOT_FOUND_PATH))?,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes a stub page at each old path of every page, sending readers on to where the
+    /// page is now, along with the redirect files of the servers turned on in the site.
+    fn write_redirects(&self, site: &Site) -> Result<(), StandardError> {
+        let outputs = &site.config.redirects;
+        let mut netlify = String::new();
+        let mut nginx = String::from(
+            "# include in the http block, and redirect from the server block with\n\
+             # if ($redirect_target) { return 301 $redirect_target; }\n\
+             map $uri $redirect_target {\n",
         );
 
-        generator
+        for page in site.iter() {
+            let href = page_href(&page.path);
+
+            for old_path in &page.redirect_from {
+                if outputs.enabled("stubs") {
+                    let canonical = match &site.config.url {
+                        Some(url) => format!("{}{href}", url.trim_end_matches('/')),
+                        None => href.clone(),
+                    };
+                    let stub_dir = self.output_dir.join(old_path.trim_start_matches('/'));
+                    create_output_dir(&stub_dir)?;
+
+                    write_output(
+                        &stub_dir.join("index.html"),
+                        self.templates.render(
+                            "redirect.html",
+                            context! {
+                                page => context! {
+                                    title => &page.name,
+                                    href => &href,
+                                },
+                                canonical => canonical,
+                            },
+                        )?,
+                    )?;
+                }
+
+                let old_href = page_href(old_path);
+                let old_path = old_href.trim_end_matches('/');
+
+                netlify.push_str(&format!("{old_href} {href} 301\n"));
+                // nginx matches $uri exactly, so both forms are listed
+                nginx.push_str(&format!("    {old_href} {href};\n"));
+
+                if !old_path.is_empty() {
+                    nginx.push_str(&format!("    {old_path} {href};\n"));
+                }
+            }
+        }
+
+        nginx.push_str("}\n");
+
+        if outputs.enabled("netlify") {
+            write_output(&self.output_dir.join("_redirects"), netlify)?;
+        }
+
+        if outputs.enabled("nginx") {
+            write_output(&self.output_dir.join("redirects.map"), nginx)?;
+        }
+
+        Ok(())
     }
 
-    pub fn generate(&mut self, root_page: &Page) {
-        for page in &root_page.subpages {
-            if !page.path.starts_with("/") {
-                println!("The path of a page must start with a slash '/'");
-                return;
+    /// Makes sure every layout a page asks for exists before anything is rendered.
+    fn check_layouts(&self, site: &Site) -> Result<(), StandardError> {
+        let layouts = self.templates.layouts();
+
+        for page in site.iter() {
+            let Some(layout) = &page.layout else {
+                continue;
+            };
+
+            if !layouts.contains(layout) {
+                let (pos_start, pos_end) = page.keyword_range();
+                let layout_names = layouts.iter().map(String::as_str).collect::<Vec<_>>();
+
+                return Err(StandardError::new(
+                    &format!("unknown layout '{}' for page '{}'", layout, page.name),
+                    pos_start,
+                    pos_end,
+                    Some(&format!(
+                        "available layouts are {}",
+                        list_options(&layout_names)
+                    )),
+                ));
             }
+        }
+
+        Ok(())
+    }
+
+    /// Warns about pages a `nav` block leaves no way to reach, as they aren't listed in it
+    /// or under a page that is.
+    fn check_nav(&mut self, site: &Site, outline: &Outline) {
+        let Some(nav) = &site.nav else {
+            return;
+        };
+        let mut reachable = HashSet::new();
 
-            if page.path == "/" {
-                fs::write(self.output_dir.join("index.html"), self.render_page(&page))
-                    .expect("Error writing root index.html");
+        for entry in nav.iter().flat_map(|entry| entry.iter()) {
+            if let NavEntry::Page { path, .. } = entry
+                && let Some(page) = site
+                    .iter()
+                    .find(|page| page.path.trim_end_matches('/') == path.trim_end_matches('/'))
+            {
+                reachable.extend(page.iter().map(|page| page.path.as_str()));
+            }
+        }
 
-                self.generate(&page);
+        for page in site.iter() {
+            if reachable.contains(page.path.as_str()) || outline.hidden(&page.path) {
                 continue;
             }
 
-            let page_dir = self.output_dir.join(&page.path.trim_start_matches('/'));
-            fs::create_dir_all(&page_dir).expect("Error creating page directory");
+            let (pos_start, pos_end) = page.keyword_range();
+
+            self.warnings.push(StandardWarning::new(
+                &format!("page '{}' isn't in the nav block", page.path),
+                pos_start,
+                pos_end,
+                Some(&format!(
+                    "add it with page \"{}\" in the nav block, or make it hidden",
+                    page.path
+                )),
+            ));
+        }
+    }
 
-            fs::write(page_dir.join("index.html"), self.render_page(&page))
-                .expect("Error writing page index.html");
+    fn check_unused_snippets(&mut self) {
+        for snippet in self.directives.unused_snippets() {
+            let (pos_start, pos_end) = snippet.name_range();
 
-            self.generate(&page);
+            self.warnings.push(StandardWarning::new(
+                &format!("unused snippet '{}'", snippet.name),
+                pos_start,
+                pos_end,
+                Some(&format!(
+                    "include it in a page with {{{{#snippet {}}}}} or remove it",
+                    snippet.name
+                )),
+            ));
         }
     }
 
-    pub fn build_navigation(&mut self, page: &Page, level: usize) {
-        for page in &page.subpages {
-            let href = if page.path == "/" {
-                "/"
-            } else {
-                &format!("{}/", page.path.trim_end_matches('/'))
+    /// Warns about links to paths of the site that no page is built at, pointing out the
+    /// pages that exist but were left out by the active profiles.
+    fn check_links(&mut self, page: &Page, rendered: &RenderedMarkdown) {
+        for link in &rendered.links {
+            if !link.starts_with('/') || link.starts_with("//") {
+                continue;
+            }
+
+            let path = link
+                .split(['#', '?'])
+                .next()
+                .unwrap_or("")
+                .trim_end_matches('/');
+
+            // files such as /logo.png aren't pages
+            if path
+                .rsplit('/')
+                .next()
+                .is_some_and(|part| part.contains('.'))
+                || self.page_paths.contains(path)
+            {
+                continue;
+            }
+
+            let markdown = dedent(&page.markdown_contents);
+            let (pos_start, pos_end) = match markdown
+                .lines()
+                .position(|line| line.contains(link.as_str()))
+            {
+                Some(line) => page.contents_range(line, link),
+                None => page.keyword_range(),
+            };
+
+            if let Some(target) = self.redirects.get(path) {
+                self.warnings.push(StandardWarning::new(
+                    &format!("link to '{link}', which redirects to '{target}'"),
+                    pos_start,
+                    pos_end,
+                    Some(&format!("link to '{}' instead", page_href(target))),
+                ));
+
+                continue;
+            }
+
+            let excluded = self
+                .excluded_pages
+                .iter()
+                .find(|(excluded, _)| excluded.trim_end_matches('/') == path);
+
+            let warning = match excluded {
+                Some((excluded, reason)) => StandardWarning::new(
+                    &format!("link to '{excluded}', which this build leaves out"),
+                    pos_start,
+                    pos_end,
+                    Some(reason),
+                ),
+                None => {
+                    let paths = self
+                        .page_paths
+                        .iter()
+                        .map(String::as_str)
+                        .collect::<Vec<_>>();
+                    let help = closest_match(path, &paths)
+                        .map(|suggestion| format!("did you mean '{}'?", page_href(suggestion)));
+
+                    StandardWarning::new(
+                        &format!("link to '{link}', which is not a page of the site"),
+                        pos_start,
+                        pos_end,
+                        help.as_deref(),
+                    )
+                }
             };
 
-            self.navigation_html.push_str(&format!(
-                "<a href=\"{}\">{}{}</a><br>",
-                href,
-                "· ".repeat(level),
-                &page.name
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Warns about descriptions too long for search engines to show whole.
+    fn check_description(&mut self, description: &str, range: (Position, Position)) {
+        let length = description.chars().count();
+
+        if length <= MAX_DESCRIPTION_LENGTH {
+            return;
+        }
+
+        let (pos_start, pos_end) = range;
+
+        self.warnings.push(StandardWarning::new(
+            &format!("description is {length} characters long"),
+            pos_start,
+            pos_end,
+            Some(&format!(
+                "search engines cut descriptions off after about {MAX_DESCRIPTION_LENGTH} characters"
+            )),
+        ));
+    }
+
+    /// Warns about syntax of Markdown extensions that are turned off.
+    fn check_disabled_syntax(&mut self, page: &Page) {
+        let markdown = dedent(&page.markdown_contents);
+
+        for syntax in find_disabled_syntax(&markdown, &self.markdown_options.extensions) {
+            let (pos_start, pos_end) = page.contents_range(syntax.line, &syntax.text);
+
+            self.warnings.push(StandardWarning::new(
+                &format!(
+                    "this looks like {}, but the '{}' extension is turned off",
+                    syntax.description, syntax.extension
+                ),
+                pos_start,
+                pos_end,
+                Some(&format!(
+                    "turn it on with '{}: true' in the 'markdown' block of the site",
+                    syntax.extension
+                )),
+            ));
+        }
+    }
+
+    /// Warns about parts of the line ranges of code blocks that can't be highlighted.
+    fn check_line_ranges(&mut self, page: &Page) {
+        for code_block in find_invalid_line_ranges(&dedent(&page.markdown_contents)) {
+            for range in code_block.ranges {
+                let (pos_start, pos_end) = page.contents_range(code_block.line, &range.text);
+
+                self.warnings.push(StandardWarning::new(
+                    &range.message,
+                    pos_start,
+                    pos_end,
+                    Some(&range.help),
+                ));
+            }
+        }
+    }
+
+    /// Warns about lines of tab groups before their first tab, which aren't shown.
+    fn check_stray_tab_lines(&mut self, page: &Page) {
+        for stray in find_stray_tab_lines(&dedent(&page.markdown_contents)) {
+            let (pos_start, pos_end) = page.contents_range(stray.line, &stray.text);
+
+            self.warnings.push(StandardWarning::new(
+                "this line is in a tab group, but not in any of its tabs, so it isn't shown",
+                pos_start,
+                pos_end,
+                Some("move it under a '::tab' line, or above ':::tabs'"),
             ));
+        }
+    }
+
+    /// The headings listed in the table of contents of `page`, none when it has `toc: false`.
+    fn page_toc<'r>(&self, page: &Page, rendered: &'r RenderedMarkdown) -> Vec<&'r Heading> {
+        if page.toc {
+            table_of_contents(&rendered.headings, self.toc_levels)
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn render_page(
+        &self,
+        page: &Page,
+        rendered: &RenderedMarkdown,
+        trail: PageTrail,
+    ) -> Result<String, StandardError> {
+        let layout = page.layout.as_deref().unwrap_or(DEFAULT_LAYOUT);
+        let toc = self.page_toc(page, rendered);
+
+        self.templates.render(
+            &format!("layouts/{layout}.html"),
+            context! {
+                page => context! {
+                    title => &page.name,
+                    path => &page.path,
+                    href => page_href(&page.path),
+                    layout => layout,
+                    date => &page.date,
+                    states => if self.preview { page.states() } else { Vec::new() },
+                },
+                children => Value::from_serialize(
+                    listed(&page.subpages).map(ChildPage::new).collect::<Vec<_>>(),
+                ),
+                meta => Value::from_serialize(PageMeta::new(page, &self.config)),
+                content => Value::from_safe_string(rendered.html.clone()),
+                toc => Value::from_serialize(toc),
+                search => self.search_index.is_some(),
+                trail => Value::from_serialize(trail),
+                navigation => Value::from_serialize(
+                    self.navigation.for_page(&page.path, self.navigation_depth),
+                ),
+            },
+        )
+    }
+}
+
+/// Creates `path` and the directories above it, pointing at it when that fails.
+fn create_output_dir(path: &Path) -> Result<(), StandardError> {
+    fs::create_dir_all(path).map_err(|error| output_error("create the directory", path, error))
+}
+
+/// Writes `contents` to `path`, pointing at it when that fails.
+fn write_output(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), StandardError> {
+    fs::write(path, contents).map_err(|error| output_error("write", path, error))
+}
+
+fn output_error(action: &str, path: &Path, error: io::Error) -> StandardError {
+    let filename = path.display().to_string();
+    let pos_start = Position::new(0, 0, 0, &filename, "");
+    let mut pos_end = pos_start.clone();
+    pos_end.advance(None);
+
+    StandardError::new(
+        &format!("could not {action} '{filename}': {error}"),
+        pos_start,
+        pos_end,
+        Some("check that the output directory can be written to"),
+    )
+}
+
+/// The URL a page is served from: its directory, with a trailing slash.
+pub fn page_href(path: &str) -> String {
+    if path == "/" {
+        "/".to_string()
+    } else {
+        format!("{}/", path.trim_end_matches('/'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{OutputFormat, RenderOptions, generate_site, parse_str, render};
+    use std::{fs, path::PathBuf};
+
+    /// Builds `code` into a directory unique to the test, returning the directory.
+    fn build(test: &str, code: &str) -> (PathBuf, Vec<String>) {
+        let output_dir = std::env::temp_dir().join(format!("dl-generator-{test}"));
+        let _ = fs::remove_dir_all(&output_dir);
+        let warnings = generate_site("test.dl", output_dir.to_str().unwrap(), code)
+            .unwrap()
+            .into_iter()
+            .map(|warning| warning.text)
+            .collect();
+
+        (output_dir, warnings)
+    }
 
-            self.build_navigation(page, level + 1);
+    const REDIRECTS: &str = r#"
+        site {
+            url: "https://docs.example.com"
+            redirects { netlify: true nginx: true }
         }
+
+        page { name: "Home" path: "/" contents: "See [the guide](/setup/)." }
+        page {
+            name: "Install"
+            path: "/guides/install"
+            contents: ""
+            redirect_from: "/setup, /old/install/"
+        }
+    "#;
+
+    #[test]
+    fn redirects_are_written_for_every_output() {
+        let (output_dir, _) = build("redirects", REDIRECTS);
+        let stub = fs::read_to_string(output_dir.join("setup/index.html")).unwrap();
+
+        assert!(stub.contains("content=\"0; url=&#x2f;guides&#x2f;install&#x2f;\""));
+        assert!(stub.contains("https:&#x2f;&#x2f;docs.example.com&#x2f;guides&#x2f;install&#x2f;"));
+        assert!(output_dir.join("old/install/index.html").is_file());
+        assert_eq!(
+            fs::read_to_string(output_dir.join("_redirects")).unwrap(),
+            "/setup/ /guides/install/ 301\n/old/install/ /guides/install/ 301\n"
+        );
+
+        let nginx = fs::read_to_string(output_dir.join("redirects.map")).unwrap();
+
+        assert!(nginx.contains("    /setup/ /guides/install/;\n    /setup /guides/install/;\n"));
+    }
+
+    #[test]
+    fn only_stubs_are_written_by_default() {
+        let code = REDIRECTS.replace("redirects { netlify: true nginx: true }", "");
+        let (output_dir, _) = build("default-redirects", &code);
+
+        assert!(output_dir.join("setup/index.html").is_file());
+        assert!(!output_dir.join("_redirects").exists());
+        assert!(!output_dir.join("redirects.map").exists());
+    }
+
+    #[test]
+    fn links_to_old_paths_are_pointed_out() {
+        let (_, warnings) = build("redirect-links", REDIRECTS);
+
+        assert!(
+            warnings
+                .contains(&"link to '/setup/', which redirects to '/guides/install'".to_string())
+        );
+    }
+
+    #[test]
+    fn json_documents_follow_the_toc_settings() {
+        let output_dir = std::env::temp_dir().join("dl-generator-json-toc");
+        let _ = fs::remove_dir_all(&output_dir);
+        let site = parse_str(
+            "test.dl",
+            r###"
+            site { toc_min_level: 2 toc_max_level: 2 }
+            page {
+                name: "Home"
+                path: "/"
+                contents: "
+                    # Title
+                    ## Intro
+                    ### Detail
+                "
+            }
+            page { name: "Plain" path: "/plain" contents: "## Intro" toc: false }
+            "###,
+        )
+        .unwrap();
+        let options =
+            RenderOptions::new(output_dir.to_str().unwrap()).with_formats(&[OutputFormat::Json]);
+
+        render(&site, &options).unwrap();
+
+        let document = |path: &str| -> serde_json::Value {
+            serde_json::from_str(&fs::read_to_string(output_dir.join(path)).unwrap()).unwrap()
+        };
+        let home = document("index.json");
+
+        assert_eq!(home["toc"].as_array().unwrap().len(), 1);
+        assert_eq!(home["toc"][0]["text"], "Intro");
+        assert_eq!(document("plain/index.json")["toc"], serde_json::json!([]));
     }
 
-    fn render_page(&self, page: &Page) -> String {
-        let page_html = include_str!("../../templates/page.html");
-        let page_markdown = page.markdown_contents.clone();
-        let page_markdown = page_markdown
-            .lines()
-            .map(|line| line.trim_start())
-            .collect::<Vec<_>>()
-            .join("\n");
+    #[test]
+    fn output_that_cannot_be_written_is_an_error() {
+        let output_file = std::env::temp_dir().join("dl-generator-test-unwritable");
+        let _ = fs::remove_dir_all(&output_file);
+        fs::write(&output_file, "").unwrap();
 
-        let parser = pulldown_cmark::Parser::new(&page_markdown);
-        let mut html_output = String::new();
+        let error = generate_site(
+            "test.dl",
+            output_file.join("site").to_str().unwrap(),
+            r#"page { name: "Home" path: "/" contents: "" }"#,
+        )
+        .unwrap_err();
 
-        pulldown_cmark::html::push_html(&mut html_output, parser);
+        assert!(error.text.starts_with("could not create the directory"));
+    }
+
+    #[test]
+    fn invalid_line_ranges_are_warnings() {
+        let code = r#"
+            page {
+                name: "Home"
+                path: "/"
+                contents: "
+                    ```rust {5-3,2}
+                    let a = 1;
+                    ```
+                "
+            }
+        "#;
+        let (_, warnings) = build("line-ranges", code);
 
-        page_html
-            .replace("PAGE_TITLE", &page.name)
-            .replace("PAGE_CONTENT", &html_output)
-            .replace("PAGE_NAVIGATION", &self.navigation_html)
+        assert_eq!(warnings, ["line range '5-3' ends before it starts"]);
     }
 }
@@ -0,0 +1,178 @@
+use pulldown_cmark::{CowStr, Event, Tag, TagEnd};
+use serde::Serialize;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Heading {
+    pub level: u8,
+    pub text: String,
+    /// The anchor of the heading, unique within its page.
+    pub id: String,
+}
+
+/// Gives every heading an id, unless it already has one, and appends a permalink to it.
+/// Ids are slugs of the heading text, numbered when the same text appears more than once.
+pub fn anchor_headings<'a>(events: Vec<Event<'a>>) -> (Vec<Event<'a>>, Vec<Heading>) {
+    let mut output = Vec::with_capacity(events.len());
+    let mut headings = Vec::new();
+    let mut used_ids = HashSet::new();
+    let mut events = events.into_iter();
+
+    while let Some(event) = events.next() {
+        let Event::Start(Tag::Heading {
+            level,
+            id,
+            classes,
+            attrs,
+        }) = event
+        else {
+            output.push(event);
+            continue;
+        };
+
+        let mut inner = Vec::new();
+        let mut text = String::new();
+
+        for event in events.by_ref() {
+            match &event {
+                Event::End(TagEnd::Heading(_)) => break,
+                Event::Text(content) | Event::Code(content) => text.push_str(content),
+                _ => {}
+            }
+
+            inner.push(event);
+        }
+
+        let id = match id {
+            Some(id) => unique_id(&id, &mut used_ids),
+            None => unique_id(&slugify(&text), &mut used_ids),
+        };
+
+        output.push(Event::Start(Tag::Heading {
+            level,
+            id: Some(CowStr::from(id.clone())),
+            classes,
+            attrs,
+        }));
+        output.extend(inner);
+        output.push(Event::InlineHtml(CowStr::from(format!(
+            "<a class=\"permalink\" href=\"#{id}\" aria-label=\"Permalink to this heading\">#</a>"
+        ))));
+        output.push(Event::End(TagEnd::Heading(level)));
+
+        headings.push(Heading {
+            level: level as u8,
+            text,
+            id,
+        });
+    }
+
+    (output, headings)
+}
+
+/// The headings listed in a table of contents, those between the `(min, max)` levels.
+pub fn table_of_contents(headings: &[Heading], (min_level, max_level): (u8, u8)) -> Vec<&Heading> {
+    headings
+        .iter()
+        .filter(|heading| (min_level..=max_level).contains(&heading.level))
+        .collect()
+}
+
+/// Lowercases `text` and joins its words with dashes, dropping punctuation.
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+
+    for character in text.trim().chars() {
+        if character.is_alphanumeric() {
+            slug.extend(character.to_lowercase());
+        } else if (character.is_whitespace() || character == '-' || character == '_')
+            && !slug.ends_with('-')
+        {
+            slug.push('-');
+        }
+    }
+
+    let slug = slug.trim_matches('-');
+
+    if slug.is_empty() {
+        "section".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+pub(crate) fn unique_id(base: &str, used_ids: &mut HashSet<String>) -> String {
+    let mut id = base.to_string();
+    let mut count = 1;
+
+    while !used_ids.insert(id.clone()) {
+        id = format!("{base}-{count}");
+        count += 1;
+    }
+
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{slugify, table_of_contents};
+    use crate::generation::markdown::{MarkdownOptions, render_markdown};
+
+    #[test]
+    fn headings_get_anchors() {
+        let rendered = render_markdown(
+            "## Getting started\n\n### Step `one`",
+            &MarkdownOptions::default(),
+        );
+        let ids = rendered
+            .headings
+            .iter()
+            .map(|heading| heading.id.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(ids, ["getting-started", "step-one"]);
+        assert_eq!(rendered.headings[1].text, "Step one");
+        assert!(rendered.html.contains("<h2 id=\"getting-started\">"));
+        assert!(rendered.html.contains("href=\"#getting-started\""));
+    }
+
+    #[test]
+    fn duplicate_headings_are_numbered() {
+        let rendered = render_markdown(
+            "## Usage\n\n## Usage\n\n## Usage",
+            &MarkdownOptions::default(),
+        );
+        let ids = rendered
+            .headings
+            .iter()
+            .map(|heading| heading.id.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(ids, ["usage", "usage-1", "usage-2"]);
+    }
+
+    #[test]
+    fn slugs_drop_punctuation() {
+        assert_eq!(slugify("  What's new in 2.0? "), "whats-new-in-20");
+        assert_eq!(slugify("snake_case - and dashes"), "snake-case-and-dashes");
+        assert_eq!(slugify("?!"), "section");
+    }
+
+    #[test]
+    fn the_toc_keeps_the_configured_levels() {
+        let rendered = render_markdown(
+            "# Title\n\n## Intro\n\n### Detail\n\n#### Deeper",
+            &MarkdownOptions::default(),
+        );
+        let levels = |levels| {
+            table_of_contents(&rendered.headings, levels)
+                .iter()
+                .map(|heading| heading.level)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(levels((2, 3)), [2, 3]);
+        assert_eq!(levels((1, 6)), [1, 2, 3, 4]);
+        assert_eq!(levels((4, 4)), [4]);
+    }
+}
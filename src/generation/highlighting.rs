@@ -0,0 +1,428 @@
+use crate::generation::markdown::{MarkdownOptions, escape_html};
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, Tag, TagEnd};
+use std::{ops::RangeInclusive, sync::LazyLock};
+use syntect::{
+    html::{ClassStyle, line_tokens_to_classed_spans},
+    parsing::{ParseState, ScopeStack, SyntaxDefinition, SyntaxReference, SyntaxSet},
+    util::LinesWithEndings,
+};
+
+const CLASS_PREFIX: &str = "hl-";
+
+/// The default syntaxes of syntect plus the ones DocsLegend bundles. Building it takes a
+/// moment, so it's done once, the first time a code block is highlighted.
+static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(|| {
+    let mut builder = SyntaxSet::load_defaults_newlines().into_builder();
+    let toml = SyntaxDefinition::load_from_str(
+        include_str!("../../resources/syntaxes/TOML.sublime-syntax"),
+        true,
+        None,
+    )
+    .expect("Error loading the bundled TOML syntax");
+
+    builder.add(toml);
+    builder.build()
+});
+
+/// What the info string of a fenced code block asks for, e.g.
+/// ```` ```rust {3-5} filename="src/main.rs" linenos ````.
+#[derive(Debug, Clone, Default)]
+pub struct CodeBlockInfo {
+    pub language: Option<String>,
+    pub highlighted_lines: Vec<RangeInclusive<usize>>,
+    /// The parts of the line ranges that aren't lines or ranges, with why.
+    pub invalid_ranges: Vec<InvalidLineRange>,
+    pub filename: Option<String>,
+    pub line_numbers: Option<bool>,
+}
+
+impl CodeBlockInfo {
+    pub fn parse(info: &str) -> Self {
+        let mut block_info = Self::default();
+        let mut rest = info.trim();
+
+        if !rest.starts_with('{') {
+            let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let language = &rest[..end];
+
+            if !language.is_empty() {
+                block_info.language = Some(language.to_string());
+            }
+
+            rest = rest[end..].trim_start();
+        }
+
+        while !rest.is_empty() {
+            if let Some(ranges) = rest.strip_prefix('{') {
+                let end = ranges.find('}').unwrap_or(ranges.len());
+                for part in ranges[..end].split(',').map(str::trim) {
+                    match parse_line_range(part) {
+                        Ok(Some(range)) => block_info.highlighted_lines.push(range),
+                        Ok(None) => {}
+                        Err(invalid) => block_info.invalid_ranges.push(invalid),
+                    }
+                }
+
+                rest = ranges[(end + 1).min(ranges.len())..].trim_start();
+                continue;
+            }
+
+            let end = token_end(rest);
+            let token = &rest[..end];
+            rest = rest[end..].trim_start();
+
+            match token.split_once('=') {
+                Some(("filename" | "title", value)) => {
+                    block_info.filename = Some(value.trim_matches('"').to_string());
+                }
+                _ if token == "linenos" => block_info.line_numbers = Some(true),
+                _ if token == "nolinenos" => block_info.line_numbers = Some(false),
+                _ => {}
+            }
+        }
+
+        block_info
+    }
+
+    fn is_highlighted(&self, line_number: usize) -> bool {
+        self.highlighted_lines
+            .iter()
+            .any(|range| range.contains(&line_number))
+    }
+}
+
+/// The end of the first whitespace separated token, treating quoted text as one token.
+fn token_end(text: &str) -> usize {
+    let mut in_quotes = false;
+
+    for (index, character) in text.char_indices() {
+        match character {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => return index,
+            _ => {}
+        }
+    }
+
+    text.len()
+}
+
+/// A part of the line ranges of a code block that was left out, such as `5-3` or `0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidLineRange {
+    pub text: String,
+    pub message: String,
+    pub help: String,
+}
+
+/// A code block whose line ranges have parts that were left out, for warnings.
+#[derive(Debug, Clone)]
+pub struct InvalidLineRanges {
+    /// The line of the dedented contents the code block starts on.
+    pub line: usize,
+    pub ranges: Vec<InvalidLineRange>,
+}
+
+/// Parses one part of line ranges such as `3-5,8`. Empty parts, as in `{3,}`, are nothing.
+fn parse_line_range(part: &str) -> Result<Option<RangeInclusive<usize>>, InvalidLineRange> {
+    let invalid = |message: String, help: &str| InvalidLineRange {
+        text: part.to_string(),
+        message,
+        help: help.to_string(),
+    };
+
+    if part.is_empty() {
+        return Ok(None);
+    }
+
+    let (start, end) = part.split_once('-').unwrap_or((part, part));
+    let (Ok(start), Ok(end)) = (start.trim().parse::<usize>(), end.trim().parse::<usize>()) else {
+        return Err(invalid(
+            format!("'{part}' is not a line or a range of lines"),
+            "write lines and ranges like {3-5,8}",
+        ));
+    };
+
+    if start == 0 {
+        return Err(invalid(
+            format!("line range '{part}' starts at line 0"),
+            "the first line of a code block is line 1",
+        ));
+    }
+
+    if start > end {
+        return Err(invalid(
+            format!("line range '{part}' ends before it starts"),
+            &format!("write the first line first, e.g. '{end}-{start}'"),
+        ));
+    }
+
+    Ok(Some(start..=end))
+}
+
+/// Looks for line ranges in the info strings of code blocks that can't be highlighted, so
+/// that they can be pointed out instead of silently being left out.
+pub fn find_invalid_line_ranges(markdown: &str) -> Vec<InvalidLineRanges> {
+    let mut found = Vec::new();
+    let mut fence: Option<&str> = None;
+
+    for (index, line) in markdown.lines().enumerate() {
+        let trimmed = line.trim();
+
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+
+            continue;
+        }
+
+        let Some(marker) = ["```", "~~~"]
+            .into_iter()
+            .find(|marker| trimmed.starts_with(marker))
+        else {
+            continue;
+        };
+
+        fence = Some(marker);
+
+        let info = CodeBlockInfo::parse(trimmed.trim_start_matches(&marker[..1]));
+
+        if !info.invalid_ranges.is_empty() {
+            found.push(InvalidLineRanges {
+                line: index,
+                ranges: info.invalid_ranges,
+            });
+        }
+    }
+
+    found
+}
+
+fn find_syntax(language: &str) -> Option<&'static SyntaxReference> {
+    let language = match language.to_lowercase().as_str() {
+        "shell" | "console" | "shell-session" => "bash".to_string(),
+        "javascript" | "jsx" => "js".to_string(),
+        other => other.to_string(),
+    };
+
+    SYNTAX_SET.find_syntax_by_token(&language)
+}
+
+/// Replaces every code block with its highlighted HTML.
+pub fn highlight_code_blocks<'a>(
+    events: Vec<Event<'a>>,
+    options: &MarkdownOptions,
+) -> Vec<Event<'a>> {
+    let mut output = Vec::with_capacity(events.len());
+    let mut events = events.into_iter();
+
+    while let Some(event) = events.next() {
+        let Event::Start(Tag::CodeBlock(kind)) = event else {
+            output.push(event);
+            continue;
+        };
+
+        let info = match kind {
+            CodeBlockKind::Fenced(info) => info.to_string(),
+            CodeBlockKind::Indented => String::new(),
+        };
+        let mut code = String::new();
+
+        for event in events.by_ref() {
+            match event {
+                Event::End(TagEnd::CodeBlock) => break,
+                Event::Text(text) => code.push_str(&text),
+                _ => {}
+            }
+        }
+
+        output.push(Event::Html(CowStr::from(highlight_code_block(
+            &code, &info, options,
+        ))));
+    }
+
+    output
+}
+
+/// Renders a code block as HTML, with a span per token whose classes come from the
+/// scopes of the syntax, so `style.css` decides the colors for the light and dark themes.
+pub fn highlight_code_block(code: &str, info: &str, options: &MarkdownOptions) -> String {
+    let block_info = CodeBlockInfo::parse(info);
+    let line_numbers = block_info.line_numbers.unwrap_or(options.line_numbers);
+    let syntax = block_info.language.as_deref().and_then(find_syntax);
+    let mut parse_state = syntax.map(ParseState::new);
+    let mut scope_stack = ScopeStack::new();
+    let mut lines_html = String::new();
+
+    for (index, line) in LinesWithEndings::from(code).enumerate() {
+        let line_number = index + 1;
+        let line = line.trim_end_matches('\n');
+        let mut classes = String::from("code-line");
+
+        if block_info.is_highlighted(line_number) {
+            classes.push_str(" highlighted");
+        }
+
+        lines_html.push_str(&format!("<span class=\"{classes}\">"));
+
+        if line_numbers {
+            lines_html.push_str(&format!(
+                "<span class=\"line-number\" aria-hidden=\"true\">{line_number}</span>"
+            ));
+        }
+
+        match parse_state.as_mut() {
+            Some(parse_state) => {
+                lines_html.push_str(&highlight_line(line, parse_state, &mut scope_stack))
+            }
+            None => lines_html.push_str(&escape_html(line)),
+        }
+
+        lines_html.push_str("</span>\n");
+    }
+
+    let language = block_info.language.as_deref().unwrap_or("text");
+    let mut html = String::from("<figure class=\"code-block\">");
+
+    if let Some(filename) = &block_info.filename {
+        html.push_str(&format!(
+            "<figcaption class=\"code-filename\">{}</figcaption>",
+            escape_html(filename)
+        ));
+    }
+
+    html.push_str(&format!(
+        "<pre class=\"code\"><code class=\"language-{}\">{lines_html}</code></pre></figure>\n",
+        escape_html(language)
+    ));
+
+    html
+}
+
+/// Highlights one line. Spans still open from earlier lines are reopened at its start and
+/// every span is closed at its end, so each line can be wrapped in its own element.
+fn highlight_line(
+    line: &str,
+    parse_state: &mut ParseState,
+    scope_stack: &mut ScopeStack,
+) -> String {
+    let style = ClassStyle::SpacedPrefixed {
+        prefix: CLASS_PREFIX,
+    };
+    let mut html = String::new();
+
+    for scope in scope_stack.as_slice() {
+        html.push_str(&format!(
+            "<span class=\"{}\">",
+            scope_classes(&scope.build_string())
+        ));
+    }
+
+    // syntaxes expect lines to end with a newline, which is dropped again afterwards
+    let line = format!("{line}\n");
+    let highlighted = parse_state
+        .parse_line(&line, &SYNTAX_SET)
+        .ok()
+        .and_then(|ops| line_tokens_to_classed_spans(&line, &ops, style, scope_stack).ok());
+
+    match highlighted {
+        Some((line_html, _)) => html.push_str(&line_html.replace('\n', "")),
+        None => html.push_str(&escape_html(line.trim_end_matches('\n'))),
+    }
+
+    html.push_str(&"</span>".repeat(scope_stack.len()));
+    html
+}
+
+fn scope_classes(scope: &str) -> String {
+    scope
+        .split('.')
+        .map(|atom| format!("{CLASS_PREFIX}{atom}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CodeBlockInfo, find_invalid_line_ranges, highlight_code_block};
+    use crate::generation::markdown::MarkdownOptions;
+
+    #[test]
+    fn the_info_string_is_parsed() {
+        let info = CodeBlockInfo::parse(r#"rust {3-5,8} filename="src/main.rs" linenos"#);
+
+        assert_eq!(info.language.as_deref(), Some("rust"));
+        assert_eq!(info.highlighted_lines, [3..=5, 8..=8]);
+        assert_eq!(info.filename.as_deref(), Some("src/main.rs"));
+        assert_eq!(info.line_numbers, Some(true));
+    }
+
+    #[test]
+    fn every_part_of_the_info_string_is_optional() {
+        let info = CodeBlockInfo::parse("{2}");
+
+        assert_eq!(info.language, None);
+        assert_eq!(info.highlighted_lines, [2..=2]);
+
+        let info = CodeBlockInfo::parse(r#"toml title="my config.toml" nolinenos"#);
+
+        assert_eq!(info.filename.as_deref(), Some("my config.toml"));
+        assert_eq!(info.line_numbers, Some(false));
+        assert!(info.highlighted_lines.is_empty());
+    }
+
+    #[test]
+    fn lines_are_highlighted_and_numbered() {
+        let options = MarkdownOptions {
+            line_numbers: true,
+            ..Default::default()
+        };
+        let html = highlight_code_block("let a = 1;\nlet b = 2;\n", "rust {2}", &options);
+
+        assert!(html.contains("<code class=\"language-rust\">"));
+        assert!(html.contains("<span class=\"code-line highlighted\"><span class=\"line-number\" aria-hidden=\"true\">2</span>"));
+        assert!(html.contains("hl-keyword"));
+
+        let html = highlight_code_block("let a = 1;\n", "rust nolinenos", &options);
+        assert!(!html.contains("line-number"));
+    }
+
+    #[test]
+    fn unknown_languages_are_escaped_as_text() {
+        let html = highlight_code_block("<b>&</b>\n", "made-up", &MarkdownOptions::default());
+
+        assert!(html.contains("<code class=\"language-made-up\">"));
+        assert!(html.contains("&lt;b&gt;&amp;&lt;/b&gt;"));
+        assert!(!html.contains("hl-"));
+    }
+
+    #[test]
+    fn invalid_line_ranges_are_left_out_and_reported() {
+        let info = CodeBlockInfo::parse("rust {5-3, 0, 2, x-4, 7,}");
+        let invalid = info
+            .invalid_ranges
+            .iter()
+            .map(|range| range.message.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(info.highlighted_lines, [2..=2, 7..=7]);
+        assert_eq!(
+            invalid,
+            [
+                "line range '5-3' ends before it starts",
+                "line range '0' starts at line 0",
+                "'x-4' is not a line or a range of lines",
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_line_ranges_are_found_in_code_blocks() {
+        let markdown = "Intro\n\n```rust {4-1}\n```rust {0}\n```\n\n~~~ {3}\n~~~";
+        let found = find_invalid_line_ranges(markdown);
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].line, 2);
+        assert_eq!(found[0].ranges[0].text, "4-1");
+    }
+}
@@ -0,0 +1,234 @@
+use crate::errors::standard_error::list_options;
+use std::{fs, path::Path};
+
+/// Which part of a file an include asks for.
+#[derive(Debug, Clone, PartialEq)]
+enum Selection {
+    All,
+    /// 1-based, inclusive lines, either end left open.
+    Lines(Option<usize>, Option<usize>),
+    Anchor(String),
+}
+
+/// The text of `{{#include argument}}`, resolved against `source_dir`, or the message and
+/// help of what's wrong. `file:10:25` includes lines 10 to 25 and `file:name` the lines
+/// between `ANCHOR: name` and `ANCHOR_END: name` comments. Anchor comments are left out and
+/// the included lines are dedented.
+pub fn include(argument: &str, source_dir: &Path) -> Result<String, (String, Option<String>)> {
+    let (file, selection) = parse_argument(argument)?;
+    let text = fs::read_to_string(source_dir.join(file)).map_err(|error| {
+        (
+            format!("could not read included file '{file}': {error}"),
+            Some("paths are relative to the .dl file".to_string()),
+        )
+    })?;
+    let lines = text.lines().collect::<Vec<_>>();
+
+    let selected = match &selection {
+        Selection::All => lines,
+        Selection::Lines(start, end) => {
+            let start = start.unwrap_or(1);
+            let end = end.unwrap_or(lines.len());
+
+            if start == 0 || start > end || end > lines.len() {
+                return Err((
+                    format!("lines {start} to {end} are not in '{file}'"),
+                    Some(format!(
+                        "'{file}' has {} lines, numbered from 1",
+                        lines.len()
+                    )),
+                ));
+            }
+
+            lines[start - 1..end].to_vec()
+        }
+        Selection::Anchor(name) => anchored_lines(&lines, name).ok_or_else(|| {
+            let anchors = anchor_names(&lines);
+            let anchors = anchors.iter().map(String::as_str).collect::<Vec<_>>();
+            let help = if anchors.is_empty() {
+                format!("'{file}' has no 'ANCHOR: {name}' and 'ANCHOR_END: {name}' comments")
+            } else {
+                format!("anchors in '{file}' are {}", list_options(&anchors))
+            };
+
+            (format!("anchor '{name}' not found in '{file}'"), Some(help))
+        })?,
+    };
+
+    let kept = selected
+        .into_iter()
+        .filter(|line| anchor_comment(line).is_none())
+        .collect::<Vec<_>>();
+
+    Ok(dedent_lines(&kept))
+}
+
+/// Splits `file`, `file:10`, `file:10:25`, `file:10:`, `file::25` or `file:anchor`.
+fn parse_argument(argument: &str) -> Result<(&str, Selection), (String, Option<String>)> {
+    let mut parts = argument.splitn(3, ':');
+    let file = parts.next().unwrap_or("").trim();
+    let first = parts.next().map(str::trim);
+    let second = parts.next().map(str::trim);
+
+    if file.is_empty() {
+        return Err((
+            "missing file to include".to_string(),
+            Some("write it as {{#include path/to/file}}".to_string()),
+        ));
+    }
+
+    let line_number = |part: &str| -> Result<Option<usize>, (String, Option<String>)> {
+        if part.is_empty() {
+            return Ok(None);
+        }
+
+        part.parse().map(Some).map_err(|_| {
+            (
+                format!("invalid line number '{part}'"),
+                Some("ranges are written as file:start:end, e.g. main.rs:10:25".to_string()),
+            )
+        })
+    };
+
+    let selection = match (first, second) {
+        (None, _) => Selection::All,
+        (Some(first), None) if first.chars().all(|c| c.is_ascii_digit()) => {
+            let line = line_number(first)?;
+            Selection::Lines(line, line)
+        }
+        (Some(anchor), None) => Selection::Anchor(anchor.to_string()),
+        (Some(start), Some(end)) => Selection::Lines(line_number(start)?, line_number(end)?),
+    };
+
+    Ok((file, selection))
+}
+
+/// The name of an `ANCHOR: name` or `ANCHOR_END: name` comment on a line, if there is one.
+fn anchor_comment(line: &str) -> Option<(bool, &str)> {
+    for (marker, is_start) in [("ANCHOR_END:", false), ("ANCHOR:", true)] {
+        if let Some(index) = line.find(marker) {
+            let name = line[index + marker.len()..]
+                .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+                .find(|part| !part.is_empty())
+                .unwrap_or("");
+
+            return Some((is_start, name));
+        }
+    }
+
+    None
+}
+
+fn anchored_lines<'a>(lines: &[&'a str], name: &str) -> Option<Vec<&'a str>> {
+    let start = lines
+        .iter()
+        .position(|line| anchor_comment(line) == Some((true, name)))?;
+    let length = lines[start + 1..]
+        .iter()
+        .position(|line| anchor_comment(line) == Some((false, name)))?;
+
+    Some(lines[start + 1..start + 1 + length].to_vec())
+}
+
+fn anchor_names(lines: &[&str]) -> Vec<String> {
+    lines
+        .iter()
+        .filter_map(|line| match anchor_comment(line) {
+            Some((true, name)) => Some(name.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Removes the indentation all non-blank lines share.
+fn dedent_lines(lines: &[&str]) -> String {
+    let indentation = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    lines
+        .iter()
+        .map(|line| line.get(indentation..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::include;
+    use std::{fs, path::PathBuf};
+
+    const EXAMPLE: &str = "fn main() {\n    // ANCHOR: body\n    let answer = 42;\n    println!(\"{answer}\");\n    // ANCHOR_END: body\n}\n";
+
+    /// A directory holding `example.rs`, unique to the test so tests can run in parallel.
+    fn source_dir(test: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("dl-includes-{test}"));
+
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("example.rs"), EXAMPLE).unwrap();
+        dir
+    }
+
+    #[test]
+    fn whole_files_and_line_ranges_are_included() {
+        let dir = source_dir("ranges");
+
+        assert_eq!(
+            include("example.rs", &dir).unwrap(),
+            "fn main() {\n    let answer = 42;\n    println!(\"{answer}\");\n}"
+        );
+        assert_eq!(include("example.rs:3", &dir).unwrap(), "let answer = 42;");
+        assert_eq!(
+            include("example.rs:3:4", &dir).unwrap(),
+            "let answer = 42;\nprintln!(\"{answer}\");"
+        );
+        assert_eq!(include("example.rs:6:", &dir).unwrap(), "}");
+        assert_eq!(include("example.rs::1", &dir).unwrap(), "fn main() {");
+    }
+
+    #[test]
+    fn out_of_range_lines_are_errors() {
+        let dir = source_dir("out-of-range");
+        let (text, help) = include("example.rs:5:9", &dir).unwrap_err();
+
+        assert_eq!(text, "lines 5 to 9 are not in 'example.rs'");
+        assert_eq!(
+            help.as_deref(),
+            Some("'example.rs' has 6 lines, numbered from 1")
+        );
+        assert!(include("example.rs:0", &dir).is_err());
+        assert!(include("example.rs:4:3", &dir).is_err());
+        assert_eq!(
+            include("example.rs:3:x", &dir).unwrap_err().0,
+            "invalid line number 'x'"
+        );
+    }
+
+    #[test]
+    fn anchors_select_the_lines_between_them() {
+        let dir = source_dir("anchors");
+
+        assert_eq!(
+            include("example.rs:body", &dir).unwrap(),
+            "let answer = 42;\nprintln!(\"{answer}\");"
+        );
+
+        let (text, help) = include("example.rs:setup", &dir).unwrap_err();
+
+        assert_eq!(text, "anchor 'setup' not found in 'example.rs'");
+        assert_eq!(help.as_deref(), Some("anchors in 'example.rs' are 'body'"));
+    }
+
+    #[test]
+    fn missing_files_are_errors() {
+        let dir = source_dir("missing");
+        let (text, help) = include("missing.rs", &dir).unwrap_err();
+
+        assert!(text.starts_with("could not read included file 'missing.rs'"));
+        assert_eq!(help.as_deref(), Some("paths are relative to the .dl file"));
+        assert_eq!(include("", &dir).unwrap_err().0, "missing file to include");
+    }
+}
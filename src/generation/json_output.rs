@@ -0,0 +1,197 @@
+use crate::{
+    generation::{
+        generator::page_href, headings::Heading, markdown::RenderedMarkdown, navigation::listed,
+    },
+    parsing::{page::Page, site::Site},
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A page rendered as data, for clients that draw the docs without the HTML template.
+#[derive(Debug, Serialize)]
+pub struct PageDocument<'a> {
+    pub title: &'a str,
+    pub path: &'a str,
+    pub html: &'a str,
+    pub excerpt: &'a str,
+    /// The headings of the table of contents, as the HTML page lists them.
+    pub toc: Vec<&'a Heading>,
+    pub parent: Option<PageLink<'a>>,
+    pub children: Vec<PageLink<'a>>,
+    pub siblings: Vec<PageLink<'a>>,
+    pub metadata: BTreeMap<&'static str, String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PageLink<'a> {
+    pub title: &'a str,
+    pub path: &'a str,
+    pub href: String,
+}
+
+/// The `site.json` manifest: the page tree with where to find each page document.
+#[derive(Debug, Serialize)]
+pub struct SiteManifest<'a> {
+    pub pages: Vec<ManifestEntry<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ManifestEntry<'a> {
+    pub title: &'a str,
+    pub path: &'a str,
+    pub href: String,
+    pub document: String,
+    pub children: Vec<ManifestEntry<'a>>,
+}
+
+impl<'a> PageDocument<'a> {
+    pub fn new(
+        page: &'a Page,
+        rendered: &'a RenderedMarkdown,
+        toc: Vec<&'a Heading>,
+        parent: Option<&'a Page>,
+        siblings: &'a [Page],
+    ) -> Self {
+        let mut metadata = BTreeMap::new();
+
+        if let Some(pos_start) = &page.pos_start {
+            metadata.insert(
+                "source",
+                format!("{}:{}", pos_start.filename, pos_start.line_num + 1),
+            );
+        }
+
+        if let Some(layout) = &page.layout {
+            metadata.insert("layout", layout.clone());
+        }
+
+        if let Some(date) = &page.date {
+            metadata.insert("date", date.clone());
+        }
+
+        if !page.states().is_empty() {
+            metadata.insert("states", page.states().join(","));
+        }
+
+        if let Some(description) = &page.description {
+            metadata.insert("description", description.clone());
+        }
+
+        if !page.keywords.is_empty() {
+            metadata.insert("keywords", page.keywords.join(","));
+        }
+
+        if let Some(author) = &page.author {
+            metadata.insert("author", author.clone());
+        }
+
+        Self {
+            title: &page.name,
+            path: &page.path,
+            html: &rendered.html,
+            excerpt: &rendered.excerpt,
+            toc,
+            parent: parent.map(PageLink::new),
+            children: listed(&page.subpages).map(PageLink::new).collect(),
+            siblings: listed(siblings)
+                .filter(|sibling| sibling.path != page.path)
+                .map(PageLink::new)
+                .collect(),
+            metadata,
+        }
+    }
+}
+
+impl<'a> PageLink<'a> {
+    pub fn new(page: &'a Page) -> Self {
+        Self {
+            title: &page.name,
+            path: &page.path,
+            href: page_href(&page.path),
+        }
+    }
+}
+
+impl<'a> SiteManifest<'a> {
+    pub fn new(site: &'a Site) -> Self {
+        Self {
+            pages: listed(&site.pages).map(ManifestEntry::new).collect(),
+        }
+    }
+}
+
+impl<'a> ManifestEntry<'a> {
+    pub fn new(page: &'a Page) -> Self {
+        Self {
+            title: &page.name,
+            path: &page.path,
+            href: page_href(&page.path),
+            document: format!("{}index.json", page_href(&page.path)),
+            children: listed(&page.subpages).map(ManifestEntry::new).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PageDocument, SiteManifest};
+    use crate::{
+        generation::markdown::{MarkdownOptions, render_markdown},
+        parse_str,
+    };
+
+    const SITE: &str = r###"
+        page {
+            name: "Install"
+            path: "/install"
+            contents: "## Download"
+
+            page { name: "Linux" path: "/install/linux" contents: "Run the script." }
+        }
+
+        page { name: "Deploy" path: "/deploy" contents: "Ship it." }
+    "###;
+
+    #[test]
+    fn documents_link_to_their_relatives() {
+        let site = parse_str("test.dl", SITE).unwrap();
+        let install = &site.pages[0];
+        let rendered = render_markdown(&install.markdown_contents, &MarkdownOptions::default());
+        let document = PageDocument::new(install, &rendered, Vec::new(), None, &site.pages);
+        let json = serde_json::to_value(&document).unwrap();
+
+        assert_eq!(json["title"], "Install");
+        assert!(json["parent"].is_null());
+        assert_eq!(json["children"][0]["href"], "/install/linux/");
+        assert_eq!(json["siblings"][0]["path"], "/deploy");
+        assert_eq!(json["siblings"].as_array().unwrap().len(), 1);
+        assert_eq!(json["metadata"]["source"], "test.dl:2");
+
+        let linux = &install.subpages[0];
+        let rendered = render_markdown(&linux.markdown_contents, &MarkdownOptions::default());
+        let document = PageDocument::new(
+            linux,
+            &rendered,
+            Vec::new(),
+            Some(install),
+            &install.subpages,
+        );
+        let json = serde_json::to_value(&document).unwrap();
+
+        assert_eq!(json["parent"]["href"], "/install/");
+        assert_eq!(json["siblings"].as_array().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn the_manifest_mirrors_the_page_tree() {
+        let site = parse_str("test.dl", SITE).unwrap();
+        let json = serde_json::to_value(SiteManifest::new(&site)).unwrap();
+
+        assert_eq!(json["pages"][0]["document"], "/install/index.json");
+        assert_eq!(
+            json["pages"][0]["children"][0]["document"],
+            "/install/linux/index.json"
+        );
+        assert_eq!(json["pages"][1]["title"], "Deploy");
+    }
+}
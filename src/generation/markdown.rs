@@ -0,0 +1,405 @@
+use crate::{
+    generation::{
+        admonitions::expand_admonitions,
+        headings::{Heading, anchor_headings},
+        highlighting::highlight_code_blocks,
+        tabs::expand_tabs,
+    },
+    parsing::markdown_extensions::MarkdownExtensions,
+};
+use pulldown_cmark::{Event, Options, Tag, TagEnd};
+
+const EXCERPT_LENGTH: usize = 160;
+
+/// The HTML of a page along with what was learned about its contents while rendering it.
+#[derive(Debug, Clone)]
+pub struct RenderedMarkdown {
+    pub html: String,
+    pub headings: Vec<Heading>,
+    pub excerpt: String,
+    /// All of the text of the page without any markup, for searching.
+    pub text: String,
+    /// The destinations of the links of the page, as written.
+    pub links: Vec<String>,
+}
+
+/// Site wide settings for how Markdown is rendered.
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownOptions {
+    /// Whether code blocks show line numbers unless they say otherwise.
+    pub line_numbers: bool,
+    pub extensions: MarkdownExtensions,
+}
+
+impl MarkdownOptions {
+    fn parser_options(&self) -> Options {
+        let flags = [
+            ("tables", Options::ENABLE_TABLES),
+            ("footnotes", Options::ENABLE_FOOTNOTES),
+            ("strikethrough", Options::ENABLE_STRIKETHROUGH),
+            ("tasklists", Options::ENABLE_TASKLISTS),
+            ("heading_attributes", Options::ENABLE_HEADING_ATTRIBUTES),
+            ("definition_lists", Options::ENABLE_DEFINITION_LIST),
+            ("smart_punctuation", Options::ENABLE_SMART_PUNCTUATION),
+            ("math", Options::ENABLE_MATH),
+            // containers are expanded before parsing, see `expand_admonitions`
+        ];
+
+        flags
+            .into_iter()
+            .filter(|(name, _)| self.extensions.enabled(name))
+            .fold(Options::empty(), |options, (_, flag)| options | flag)
+    }
+}
+
+/// Syntax of an extension that is turned off, which would show up as plain text.
+#[derive(Debug, Clone)]
+pub struct DisabledSyntax {
+    pub extension: &'static str,
+    /// What the syntax looks like, e.g. "a table".
+    pub description: &'static str,
+    /// The line of the dedented contents it's on.
+    pub line: usize,
+    pub text: String,
+}
+
+pub fn render_markdown(markdown: &str, options: &MarkdownOptions) -> RenderedMarkdown {
+    let markdown = expand_admonitions(
+        &expand_tabs(&dedent(markdown)),
+        options.extensions.enabled("containers"),
+    );
+
+    let events =
+        pulldown_cmark::Parser::new_ext(&markdown, options.parser_options()).collect::<Vec<_>>();
+    let excerpt = collect_excerpt(&events);
+    let text = collect_text(&events);
+    let links = collect_links(&events);
+    let (events, headings) = anchor_headings(events);
+    let events = highlight_code_blocks(events, options);
+    let mut html = String::new();
+
+    pulldown_cmark::html::push_html(&mut html, events.into_iter());
+
+    RenderedMarkdown {
+        html,
+        headings,
+        excerpt,
+        text,
+        links,
+    }
+}
+
+/// Removes the indentation contents get from lining up with their page block, keeping any
+/// indentation beyond it, such as in code blocks. The first line follows the opening quote
+/// of the field, so it isn't considered.
+pub fn dedent(text: &str) -> String {
+    let mut lines = text.lines();
+    let first = lines.next().unwrap_or("").trim_start();
+    let rest = lines.collect::<Vec<_>>();
+    let indentation = rest
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    std::iter::once(first)
+        .chain(
+            rest.iter()
+                .map(|line| line.get(indentation..).unwrap_or("").trim_end_matches('\r')),
+        )
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Looks for syntax of the extensions that are turned off, outside of code blocks, so that
+/// it can be pointed out instead of silently rendering as text.
+pub fn find_disabled_syntax(
+    markdown: &str,
+    extensions: &MarkdownExtensions,
+) -> Vec<DisabledSyntax> {
+    let lines = markdown.lines().collect::<Vec<_>>();
+    let mut found = Vec::new();
+    let mut fence: Option<&str> = None;
+
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+
+            continue;
+        }
+
+        if let Some(marker) = ["```", "~~~"]
+            .into_iter()
+            .find(|marker| trimmed.starts_with(marker))
+        {
+            fence = Some(marker);
+            continue;
+        }
+
+        let mut push = |extension, description, text: &str| {
+            if !extensions.enabled(extension) {
+                found.push(DisabledSyntax {
+                    extension,
+                    description,
+                    line: index,
+                    text: text.to_string(),
+                });
+            }
+        };
+
+        let next_line = lines.get(index + 1).map(|line| line.trim()).unwrap_or("");
+
+        if trimmed.contains('|') && is_table_delimiter(next_line) {
+            push("tables", "a table", trimmed);
+        }
+
+        if let Some(text) = between(trimmed, "~~", "~~") {
+            push("strikethrough", "strikethrough", text);
+        }
+
+        if let Some(text) = between(trimmed, "[^", "]") {
+            push("footnotes", "a footnote", text);
+        }
+
+        let item = trimmed.trim_start_matches(['-', '*', '+']).trim_start();
+
+        if item.len() < trimmed.len()
+            && let Some(text) = ["[ ]", "[x]", "[X]"]
+                .into_iter()
+                .find(|task| item.starts_with(task))
+        {
+            push("tasklists", "a task list item", text);
+        }
+
+        if trimmed.starts_with('#')
+            && let Some(start) = trimmed.rfind(" {")
+            && trimmed.ends_with('}')
+            && trimmed[start + 2..].starts_with(['#', '.'])
+        {
+            push(
+                "heading_attributes",
+                "heading attributes",
+                &trimmed[start + 1..],
+            );
+        }
+
+        if let Some(kind) = trimmed.strip_prefix(":::")
+            && kind.starts_with(|character: char| character.is_ascii_alphabetic())
+            && !kind.starts_with("tabs")
+        {
+            push("containers", "a ':::' container", trimmed);
+        }
+
+        let previous_line = index.checked_sub(1).map(|index| lines[index].trim());
+
+        if trimmed.starts_with(": ") && previous_line.is_some_and(|line| !line.is_empty()) {
+            push("definition_lists", "a definition list", trimmed);
+        }
+
+        if trimmed.starts_with("$$") {
+            push("math", "display math", trimmed);
+        }
+    }
+
+    found
+}
+
+/// A delimiter row of a table, such as `| --- | :-: |`.
+fn is_table_delimiter(line: &str) -> bool {
+    line.contains('-')
+        && line.contains('|')
+        && line
+            .chars()
+            .all(|character| matches!(character, '|' | ':' | '-' | ' '))
+}
+
+/// The first part of `text` that starts with `open` and ends with `close`.
+fn between<'a>(text: &'a str, open: &str, close: &str) -> Option<&'a str> {
+    let start = text.find(open)?;
+    let end = text[start + open.len()..].find(close)? + start + open.len() + close.len();
+
+    Some(&text[start..end])
+}
+
+/// The plain text of the first paragraph, cut at a word boundary.
+fn collect_excerpt(events: &[Event]) -> String {
+    let mut excerpt = String::new();
+    let mut in_paragraph = false;
+
+    for event in events {
+        match event {
+            Event::Start(Tag::Paragraph) => in_paragraph = true,
+            Event::Text(text) | Event::Code(text) if in_paragraph => excerpt.push_str(text),
+            Event::SoftBreak | Event::HardBreak if in_paragraph => excerpt.push(' '),
+            Event::End(TagEnd::Paragraph) => break,
+            _ => {}
+        }
+    }
+
+    truncate_words(&excerpt, EXCERPT_LENGTH)
+}
+
+fn collect_links(events: &[Event]) -> Vec<String> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            Event::Start(Tag::Link { dest_url, .. }) => Some(dest_url.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn collect_text(events: &[Event]) -> String {
+    let mut text = String::new();
+
+    for event in events {
+        match event {
+            Event::Text(content) | Event::Code(content) => text.push_str(content),
+            Event::SoftBreak
+            | Event::HardBreak
+            | Event::End(
+                TagEnd::Paragraph
+                | TagEnd::Heading(_)
+                | TagEnd::Item
+                | TagEnd::CodeBlock
+                | TagEnd::TableCell
+                | TagEnd::BlockQuote(_),
+            ) if !text.ends_with(' ') => text.push(' '),
+            _ => {}
+        }
+    }
+
+    text.trim_end().to_string()
+}
+
+pub fn truncate_words(text: &str, max_length: usize) -> String {
+    if text.chars().count() <= max_length {
+        return text.to_string();
+    }
+
+    let cut = text.chars().take(max_length).collect::<String>();
+    let cut = match cut.rfind(' ') {
+        Some(index) => &cut[..index],
+        None => &cut,
+    };
+
+    format!("{}…", cut.trim_end())
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MarkdownOptions, find_disabled_syntax, render_markdown};
+    use crate::parsing::markdown_extensions::{MARKDOWN_EXTENSIONS, MarkdownExtensions};
+
+    /// Each extension with some Markdown using it and a piece of the HTML it renders to.
+    const EXAMPLES: [(&str, &str, &str); 9] = [
+        ("tables", "| a | b |\n| - | - |\n| 1 | 2 |", "<table>"),
+        (
+            "footnotes",
+            "Text[^1]\n\n[^1]: A note",
+            "footnote-definition",
+        ),
+        ("strikethrough", "~~gone~~", "<del>"),
+        ("tasklists", "- [x] done", "type=\"checkbox\""),
+        ("heading_attributes", "## Install {#setup}", "id=\"setup\""),
+        ("definition_lists", "Term\n: Definition", "<dl>"),
+        ("smart_punctuation", "\"quoted\"", "\u{201c}"),
+        ("containers", ":::note\nText\n:::", "admonition-title"),
+        ("math", "$$x^2$$", "math-display"),
+    ];
+
+    fn options(name: &str, enabled: bool) -> MarkdownOptions {
+        let mut extensions = MarkdownExtensions::default();
+        extensions.set(name, enabled);
+
+        MarkdownOptions {
+            extensions,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn every_extension_has_an_example() {
+        for name in MARKDOWN_EXTENSIONS {
+            assert!(EXAMPLES.iter().any(|(example, ..)| *example == name));
+        }
+    }
+
+    #[test]
+    fn extensions_render_only_when_on() {
+        for (name, markdown, html) in EXAMPLES {
+            let on = render_markdown(markdown, &options(name, true)).html;
+            let off = render_markdown(markdown, &options(name, false)).html;
+
+            assert!(on.contains(html), "{name} on: {on}");
+            assert!(!off.contains(html), "{name} off: {off}");
+        }
+    }
+
+    #[test]
+    fn math_is_off_by_default() {
+        let extensions = MarkdownExtensions::default();
+
+        assert!(!extensions.enabled("math"));
+        assert!(
+            MARKDOWN_EXTENSIONS
+                .iter()
+                .all(|name| { *name == "math" || extensions.enabled(name) })
+        );
+    }
+
+    #[test]
+    fn disabled_syntax_is_found() {
+        // quotes read fine either way, so smart punctuation has nothing to point out
+        for (name, markdown, _) in EXAMPLES
+            .into_iter()
+            .filter(|(name, ..)| *name != "smart_punctuation")
+        {
+            let off = options(name, false).extensions;
+            let on = options(name, true).extensions;
+
+            assert!(
+                find_disabled_syntax(markdown, &off)
+                    .iter()
+                    .any(|syntax| syntax.extension == name),
+                "{name}"
+            );
+            assert!(find_disabled_syntax(markdown, &on).is_empty(), "{name}");
+        }
+    }
+
+    #[test]
+    fn disabled_syntax_in_code_blocks_is_ignored() {
+        let markdown = "Text\n\n```\n~~gone~~\n: not a definition\n```\n\n~~~\n$$x$$\n~~~";
+        let mut extensions = MarkdownExtensions::default();
+
+        for name in MARKDOWN_EXTENSIONS {
+            extensions.set(name, false);
+        }
+
+        assert!(find_disabled_syntax(markdown, &extensions).is_empty());
+    }
+
+    #[test]
+    fn disabled_syntax_points_at_its_line() {
+        let extensions = options("strikethrough", false).extensions;
+        let found = find_disabled_syntax("First\n\nSome ~~old~~ text", &extensions);
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].line, 2);
+        assert_eq!(found[0].text, "~~old~~");
+        assert_eq!(found[0].description, "strikethrough");
+    }
+}
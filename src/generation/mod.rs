@@ -1 +1,19 @@
+pub mod admonitions;
+pub mod child_listing;
+pub mod directives;
 pub mod generator;
+pub mod headings;
+pub mod highlighting;
+pub mod includes;
+pub mod json_output;
+pub mod markdown;
+pub mod navigation;
+pub mod outline;
+pub mod page_meta;
+pub mod palette;
+pub mod profiles;
+pub mod render_options;
+pub mod search;
+pub mod sitemap;
+pub mod tabs;
+pub mod templates;
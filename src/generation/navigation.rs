@@ -0,0 +1,278 @@
+use crate::{
+    generation::generator::page_href,
+    parsing::{link::Link, nav_entry::NavEntry, page::Page, site::Site},
+};
+use serde::Serialize;
+
+/// One entry of the sidebar, with the state it has on the page being rendered.
+#[derive(Debug, Clone, Serialize)]
+pub struct NavigationItem {
+    /// `page`, `section` for a heading that isn't a page, or `link`.
+    pub kind: &'static str,
+    pub title: String,
+    pub path: String,
+    pub href: String,
+    /// Whether a link leaves the site.
+    pub external: bool,
+    /// Whether this is the page being rendered.
+    pub current: bool,
+    /// Whether this entry is the page being rendered or one of its ancestors, in which
+    /// case its branch is shown open.
+    pub expanded: bool,
+    pub children: Vec<NavigationItem>,
+}
+
+/// The sidebar as a tree, built once per site and specialised for every page it's
+/// rendered on. It follows the `nav` block of the site when there is one, and the page tree
+/// otherwise. Hidden pages and their subpages aren't part of it.
+#[derive(Debug, Clone, Default)]
+pub struct Navigation {
+    pub items: Vec<NavigationItem>,
+}
+
+impl Navigation {
+    pub fn new(site: &Site) -> Self {
+        Self {
+            items: match &site.nav {
+                Some(nav) => Self::entry_items(nav, site),
+                None => listed(&site.pages).map(NavigationItem::new).collect(),
+            },
+        }
+    }
+
+    /// The items of `nav` entries. Entries for pages this build leaves out are skipped, and
+    /// so are sections that end up empty.
+    fn entry_items(entries: &[NavEntry], site: &Site) -> Vec<NavigationItem> {
+        entries
+            .iter()
+            .filter_map(|entry| match entry {
+                NavEntry::Page { path, name, .. } => {
+                    let page = site.iter().find(|page| {
+                        page.path.trim_end_matches('/') == path.trim_end_matches('/')
+                    })?;
+                    let mut item = NavigationItem::new(page);
+
+                    if let Some(name) = name {
+                        item.title = name.clone();
+                    }
+
+                    (!page.hidden).then_some(item)
+                }
+                NavEntry::Section { name, entries, .. } => {
+                    let children = Self::entry_items(entries, site);
+
+                    (!children.is_empty()).then(|| NavigationItem::section(name, children))
+                }
+                NavEntry::Link(link) => Some(NavigationItem::link(link)),
+            })
+            .collect()
+    }
+
+    /// The tree as seen from the page at `current_path`, cut off below `max_depth` levels.
+    pub fn for_page(&self, current_path: &str, max_depth: Option<usize>) -> Vec<NavigationItem> {
+        let mut items = self.items.clone();
+
+        for item in &mut items {
+            item.mark(current_path);
+            item.truncate(max_depth.unwrap_or(usize::MAX).saturating_sub(1));
+        }
+
+        items
+    }
+}
+
+impl NavigationItem {
+    pub fn new(page: &Page) -> Self {
+        Self {
+            kind: "page",
+            title: page.name.clone(),
+            path: page.path.clone(),
+            href: page_href(&page.path),
+            external: false,
+            current: false,
+            expanded: false,
+            children: listed(&page.subpages).map(NavigationItem::new).collect(),
+        }
+    }
+
+    pub fn section(name: &str, children: Vec<NavigationItem>) -> Self {
+        Self {
+            kind: "section",
+            title: name.to_string(),
+            path: String::new(),
+            href: String::new(),
+            external: false,
+            current: false,
+            expanded: false,
+            children,
+        }
+    }
+
+    pub fn link(link: &Link) -> Self {
+        Self {
+            kind: "link",
+            title: link.name.clone(),
+            path: String::new(),
+            href: link.url.clone(),
+            external: link.is_external(),
+            current: false,
+            expanded: false,
+            children: Vec::new(),
+        }
+    }
+
+    /// Flags the item at `current_path` and every ancestor of it, returning whether the
+    /// current page is in this branch.
+    fn mark(&mut self, current_path: &str) -> bool {
+        self.current = self.kind == "page" && self.path == current_path;
+
+        let mut in_branch = self.current;
+
+        for child in &mut self.children {
+            in_branch |= child.mark(current_path);
+        }
+
+        self.expanded = in_branch;
+        in_branch
+    }
+
+    /// Sections don't count as a level, so their entries are cut off like the section was.
+    fn truncate(&mut self, remaining_depth: usize) {
+        if self.kind == "section" {
+            for child in &mut self.children {
+                child.truncate(remaining_depth);
+            }
+
+            return;
+        }
+
+        if remaining_depth == 0 {
+            self.children.clear();
+        }
+
+        for child in &mut self.children {
+            child.truncate(remaining_depth - 1);
+        }
+    }
+}
+
+/// The pages that aren't hidden.
+pub fn listed(pages: &[Page]) -> impl Iterator<Item = &Page> {
+    pages.iter().filter(|page| !page.hidden)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Navigation;
+    use crate::{
+        parse_str,
+        parsing::{page::Page, site::Site},
+    };
+
+    fn pages() -> Vec<Page> {
+        vec![
+            Page::new("Guides", "/guides", "", vec![])
+                .with_subpage(
+                    Page::new("Install", "/guides/install", "", vec![]).with_subpage(Page::new(
+                        "Linux",
+                        "/guides/install/linux",
+                        "",
+                        vec![],
+                    )),
+                )
+                .with_subpage(Page::new("Deploy", "/guides/deploy", "", vec![])),
+            Page::new("Reference", "/reference", "", vec![]).with_subpage(Page::new(
+                "Api",
+                "/reference/api",
+                "",
+                vec![],
+            )),
+        ]
+    }
+
+    fn site(pages: Vec<Page>) -> Site {
+        Site {
+            pages,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn the_tree_follows_the_pages() {
+        let items = Navigation::new(&site(pages())).for_page("/", None);
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].title, "Guides");
+        assert_eq!(items[0].href, "/guides/");
+        assert_eq!(
+            items[0].children[0].children[0].path,
+            "/guides/install/linux"
+        );
+        assert_eq!(items[1].children[0].title, "Api");
+    }
+
+    #[test]
+    fn only_the_current_branch_is_expanded() {
+        let items = Navigation::new(&site(pages())).for_page("/guides/install", None);
+        let install = &items[0].children[0];
+
+        assert!(items[0].expanded && !items[0].current);
+        assert!(install.expanded && install.current);
+        assert!(!install.children[0].expanded);
+        assert!(!items[0].children[1].expanded);
+        assert!(!items[1].expanded);
+    }
+
+    #[test]
+    fn navigation_depth_cuts_the_tree() {
+        let navigation = Navigation::new(&site(pages()));
+
+        let items = navigation.for_page("/", Some(1));
+        assert!(items.iter().all(|item| item.children.is_empty()));
+
+        let items = navigation.for_page("/", Some(2));
+        assert_eq!(items[0].children.len(), 2);
+        assert!(items[0].children[0].children.is_empty());
+    }
+
+    #[test]
+    fn hidden_pages_are_skipped() {
+        let mut pages = pages();
+        pages[0].subpages[0].hidden = true;
+        pages[1].hidden = true;
+
+        let items = Navigation::new(&site(pages)).for_page("/", None);
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].children.len(), 1);
+        assert_eq!(items[0].children[0].title, "Deploy");
+    }
+
+    #[test]
+    fn a_nav_block_replaces_the_page_tree() {
+        let site = parse_str(
+            "test.dl",
+            r#"
+            nav {
+                page "/reference" { name: "Api reference" }
+                section "Guides" { page "/guides/" page "/hidden" }
+                section "Empty" { page "/secret" }
+            }
+
+            page { name: "Guides" path: "/guides" contents: "" }
+            page { name: "Reference" path: "/reference" contents: "" }
+            page { name: "Hidden" path: "/hidden" contents: "" hidden: true }
+            page { name: "Secret" path: "/secret" contents: "" hidden: true }
+            "#,
+        )
+        .unwrap();
+        let items = Navigation::new(&site).for_page("/guides", None);
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].title, "Api reference");
+        assert_eq!(items[1].kind, "section");
+        assert!(items[1].expanded);
+        assert_eq!(items[1].children.len(), 1);
+        assert!(items[1].children[0].current);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{
+    generation::json_output::PageLink,
+    parsing::{nav_entry::NavEntry, page::Page, site::Site},
+};
+use serde::Serialize;
+
+/// The pages of a site in reading order, which is the depth first order of the page tree,
+/// rearranged to follow the `nav` block when there is one, along with the ancestors of
+/// each.
+pub struct Outline<'a> {
+    pub entries: Vec<OutlineEntry<'a>>,
+}
+
+pub struct OutlineEntry<'a> {
+    pub page: &'a Page,
+    pub ancestors: Vec<&'a Page>,
+}
+
+impl OutlineEntry<'_> {
+    /// Whether the page or one of its ancestors is hidden, which keeps it out of the
+    /// navigation, previous/next links and search.
+    pub fn hidden(&self) -> bool {
+        self.page.hidden || self.ancestors.iter().any(|ancestor| ancestor.hidden)
+    }
+}
+
+/// Where a page sits in the site, for breadcrumbs and previous/next links.
+#[derive(Debug, Serialize)]
+pub struct PageTrail<'a> {
+    /// The ancestors of the page from the top level down, followed by the page itself.
+    pub breadcrumbs: Vec<PageLink<'a>>,
+    pub previous: Option<PageLink<'a>>,
+    pub next: Option<PageLink<'a>>,
+}
+
+impl<'a> Outline<'a> {
+    pub fn new(site: &'a Site) -> Self {
+        let mut outline = Self {
+            entries: Vec::new(),
+        };
+        outline.push_pages(&site.pages, &[]);
+
+        if let Some(nav) = &site.nav {
+            outline.follow_nav(nav);
+        }
+
+        outline
+    }
+
+    /// Moves every page to where the nav block lists it, or its closest listed ancestor.
+    /// Pages the block doesn't reach go last. The sort is stable, so pages stay in tree
+    /// order under the page they're listed with.
+    fn follow_nav(&mut self, nav: &[NavEntry]) {
+        let listed = nav
+            .iter()
+            .flat_map(|entry| entry.iter())
+            .filter_map(|entry| match entry {
+                NavEntry::Page { path, .. } => Some(path.trim_end_matches('/')),
+                NavEntry::Section { .. } | NavEntry::Link(_) => None,
+            })
+            .collect::<Vec<_>>();
+        let position = |page: &Page| {
+            listed
+                .iter()
+                .position(|path| *path == page.path.trim_end_matches('/'))
+        };
+
+        self.entries.sort_by_key(|entry| {
+            std::iter::once(entry.page)
+                .chain(entry.ancestors.iter().rev().copied())
+                .find_map(position)
+                .unwrap_or(usize::MAX)
+        });
+    }
+
+    fn push_pages(&mut self, pages: &'a [Page], ancestors: &[&'a Page]) {
+        for page in pages {
+            self.entries.push(OutlineEntry {
+                page,
+                ancestors: ancestors.to_vec(),
+            });
+
+            let mut page_ancestors = ancestors.to_vec();
+            page_ancestors.push(page);
+
+            self.push_pages(&page.subpages, &page_ancestors);
+        }
+    }
+
+    /// Whether the page at `path` is hidden, directly or through an ancestor.
+    pub fn hidden(&self, path: &str) -> bool {
+        self.entries
+            .iter()
+            .any(|entry| entry.page.path == path && entry.hidden())
+    }
+
+    pub fn trail(&self, path: &str) -> PageTrail<'a> {
+        let Some(index) = self
+            .entries
+            .iter()
+            .position(|entry| entry.page.path == path)
+        else {
+            return PageTrail {
+                breadcrumbs: Vec::new(),
+                previous: None,
+                next: None,
+            };
+        };
+        let entry = &self.entries[index];
+
+        PageTrail {
+            breadcrumbs: entry
+                .ancestors
+                .iter()
+                .chain(std::iter::once(&entry.page))
+                .map(|page| PageLink::new(page))
+                .collect(),
+            previous: self.entries[..index]
+                .iter()
+                .rfind(|entry| !entry.hidden())
+                .map(|previous| PageLink::new(previous.page)),
+            next: self.entries[index + 1..]
+                .iter()
+                .find(|entry| !entry.hidden())
+                .map(|next| PageLink::new(next.page)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Outline;
+    use crate::parse_str;
+
+    #[test]
+    fn reading_order_follows_the_nav_block() {
+        let site = parse_str(
+            "test.dl",
+            r#"
+            nav {
+                page "/guides"
+                page "/"
+            }
+
+            page { name: "Home" path: "/" contents: "" }
+            page {
+                name: "Guides"
+                path: "/guides"
+                contents: ""
+
+                page { name: "Install" path: "/guides/install" contents: "" }
+            }
+            page { name: "Orphan" path: "/orphan" contents: "" hidden: true }
+            "#,
+        )
+        .unwrap();
+        let outline = Outline::new(&site);
+        let paths = outline
+            .entries
+            .iter()
+            .map(|entry| entry.page.path.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(paths, ["/guides", "/guides/install", "/", "/orphan"]);
+
+        let trail = outline.trail("/");
+
+        assert_eq!(trail.previous.unwrap().path, "/guides/install");
+        // hidden pages are skipped
+        assert!(trail.next.is_none());
+        assert!(outline.hidden("/orphan"));
+        assert_eq!(
+            outline.trail("/guides/install").breadcrumbs[0].path,
+            "/guides"
+        );
+    }
+}
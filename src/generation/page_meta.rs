@@ -0,0 +1,154 @@
+use crate::{
+    generation::generator::{NOT_FOUND_PATH, page_href},
+    parsing::{page::Page, site_config::SiteConfig},
+};
+use serde::Serialize;
+use serde_json::json;
+
+/// Descriptions longer than this are cut off by search engines.
+pub const MAX_DESCRIPTION_LENGTH: usize = 160;
+
+/// What the `<head>` of a page tells search engines and link previews about it, with the
+/// defaults of the site filled in.
+#[derive(Debug, Clone, Serialize)]
+pub struct PageMeta {
+    pub description: Option<String>,
+    /// The keywords separated by commas, as `<meta name="keywords">` takes them.
+    pub keywords: Option<String>,
+    pub author: Option<String>,
+    /// Absolute when the site has a `url`, as link previews need it to be.
+    pub image: Option<String>,
+    /// Only known when the page sets one or the site has a `url`.
+    pub canonical: Option<String>,
+    /// The `TechArticle` structured data of the page, ready to go in a `<script>`.
+    pub json_ld: String,
+}
+
+impl PageMeta {
+    pub fn new(page: &Page, config: &SiteConfig) -> Self {
+        let description = page
+            .description
+            .clone()
+            .or_else(|| config.description.clone());
+        let keywords = if page.keywords.is_empty() {
+            &config.keywords
+        } else {
+            &page.keywords
+        };
+        let keywords = (!keywords.is_empty()).then(|| keywords.join(", "));
+        let author = page.author.clone().or_else(|| config.author.clone());
+        let image = page
+            .image
+            .as_deref()
+            .or(config.image.as_deref())
+            .map(|image| absolute_url(image, config.url.as_deref()));
+        let canonical = match &page.canonical {
+            Some(canonical) => Some(absolute_url(canonical, config.url.as_deref())),
+            // served at every missing path, so it has no address of its own
+            None if page.path.trim_end_matches('/') == NOT_FOUND_PATH => None,
+            None => config
+                .url
+                .as_deref()
+                .map(|url| absolute_url(&page_href(&page.path), Some(url))),
+        };
+
+        let mut article = json!({
+            "@context": "https://schema.org",
+            "@type": "TechArticle",
+            "headline": page.name,
+        });
+
+        for (key, value) in [
+            ("description", &description),
+            ("keywords", &keywords),
+            ("image", &image),
+            ("url", &canonical),
+            ("datePublished", &page.date),
+        ] {
+            if let Some(value) = value {
+                article[key] = json!(value);
+            }
+        }
+
+        if let Some(author) = &author {
+            article["author"] = json!({ "@type": "Person", "name": author });
+        }
+
+        Self {
+            description,
+            keywords,
+            author,
+            image,
+            canonical,
+            // `</script>` or `<!--` in a value would end the script early or derail it
+            json_ld: article.to_string().replace('<', "\\u003c"),
+        }
+    }
+}
+
+/// `target` under the URL of the site, unless it's already a URL or the site has none.
+fn absolute_url(target: &str, site_url: Option<&str>) -> String {
+    match site_url {
+        Some(site_url) if !target.contains("://") && !target.starts_with("//") => format!(
+            "{}/{}",
+            site_url.trim_end_matches('/'),
+            target.trim_start_matches('/')
+        ),
+        _ => target.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PageMeta;
+    use crate::parse_str;
+
+    fn meta(site: &str, path: &str) -> PageMeta {
+        let site = parse_str("test.dl", site).unwrap();
+
+        PageMeta::new(site.find(path).unwrap(), &site.config)
+    }
+
+    #[test]
+    fn json_ld_cannot_end_its_script() {
+        let meta = meta(
+            r#"page { name: "</script><script>alert(1)" path: "/" contents: "" description: "<!-- a" }"#,
+            "/",
+        );
+        let article: serde_json::Value = serde_json::from_str(&meta.json_ld).unwrap();
+
+        assert!(!meta.json_ld.contains('<'));
+        assert_eq!(article["headline"], "</script><script>alert(1)");
+        assert_eq!(article["description"], "<!-- a");
+    }
+
+    #[test]
+    fn pages_fall_back_to_the_site() {
+        let site = r#"
+            site {
+                url: "https://docs.example.com/"
+                description: "The docs"
+                keywords: "docs, guides"
+                image: "/social.png"
+            }
+
+            page { name: "Home" path: "/" contents: "" keywords: "home" }
+            page { name: "Install" path: "/install" contents: "" canonical: "https://example.com/install" }
+            page { name: "Not found" path: "/404" contents: "" }
+        "#;
+        let home = meta(site, "/");
+
+        assert_eq!(home.description.as_deref(), Some("The docs"));
+        assert_eq!(home.keywords.as_deref(), Some("home"));
+        assert_eq!(
+            home.image.as_deref(),
+            Some("https://docs.example.com/social.png")
+        );
+        assert_eq!(home.canonical.as_deref(), Some("https://docs.example.com/"));
+        assert_eq!(
+            meta(site, "/install").canonical.as_deref(),
+            Some("https://example.com/install")
+        );
+        assert!(meta(site, "/404").canonical.is_none());
+    }
+}
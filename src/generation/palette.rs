@@ -0,0 +1,60 @@
+use std::collections::BTreeMap;
+
+/// The colors of the built in theme as `(name, placeholder, light, dark)`. `style.css` picks
+/// between `<placeholder>_LIGHT` and `<placeholder>_DARK` with `light-dark()`.
+const DEFAULT_COLORS: [(&str, &str, &str, &str); 10] = [
+    ("background", "BG_COLOR", "#ffffff", "#0d1117"),
+    ("foreground", "FG_COLOR", "#1f2328", "#e6edf3"),
+    ("link", "LINK_COLOR", "#0969da", "#4493f8"),
+    ("border", "BORDER_COLOR", "#d0d7de", "#30363d"),
+    ("selection", "SELECTION_COLOR", "#0969da", "#1f6feb"),
+    ("note", "NOTE_COLOR", "#0969da", "#4493f8"),
+    ("tip", "TIP_COLOR", "#1a7f37", "#3fb950"),
+    ("important", "IMPORTANT_COLOR", "#8250df", "#ab7df8"),
+    ("warning", "WARNING_COLOR", "#9a6700", "#d29922"),
+    ("caution", "CAUTION_COLOR", "#d1242f", "#f85149"),
+];
+
+/// The colors of the built in stylesheet, as `(placeholder, light, dark)`.
+#[derive(Debug, Clone)]
+pub struct Palette {
+    pub colors: Vec<(&'static str, String, String)>,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new())
+    }
+}
+
+impl Palette {
+    /// The default colors with the ones from the `palette` block of the site on top. A color
+    /// set without a `_dark` counterpart is used for both themes.
+    pub fn new(overrides: &BTreeMap<String, String>) -> Self {
+        let colors = DEFAULT_COLORS
+            .iter()
+            .map(|(name, placeholder, light, dark)| {
+                let custom_light = overrides.get(*name);
+                let custom_dark = overrides.get(&format!("{name}_dark")).or(custom_light);
+
+                (
+                    *placeholder,
+                    custom_light.map_or(*light, String::as_str).to_string(),
+                    custom_dark.map_or(*dark, String::as_str).to_string(),
+                )
+            })
+            .collect();
+
+        Self { colors }
+    }
+
+    /// Fills the color placeholders of the stylesheet in.
+    pub fn apply(&self, css: &str) -> String {
+        self.colors
+            .iter()
+            .fold(css.to_string(), |css, (placeholder, light, dark)| {
+                css.replace(&format!("{placeholder}_LIGHT"), light)
+                    .replace(&format!("{placeholder}_DARK"), dark)
+            })
+    }
+}
@@ -0,0 +1,322 @@
+use crate::{
+    errors::standard_error::StandardError,
+    lexing::position::Position,
+    parsing::{condition::Condition, page::Page, site::Site},
+};
+use std::collections::BTreeMap;
+
+const SECTION_START: &str = ":::if";
+const SECTION_ELSE: &str = ":::else";
+const SECTION_END: &str = ":::";
+
+/// One `:::if` section, or another `:::` container nested in contents.
+struct Frame {
+    is_section: bool,
+    /// Whether the lines of the current branch are kept.
+    visible: bool,
+    has_else: bool,
+    /// The line the section was opened on, to report it when it isn't closed.
+    line: usize,
+}
+
+/// Builds the part of a site the active profiles ask for. Pages whose `when` condition
+/// doesn't match, and drafts unless they're asked for, are left out with all of their
+/// subpages, and `:::if` sections of contents that don't match are blanked out:
+///
+/// ```text
+/// :::if internal
+/// Deploy with the staging credentials.
+/// :::else
+/// Ask the platform team for access.
+/// :::
+/// ```
+pub struct Profiles {
+    pub active: Vec<String>,
+    pub drafts: bool,
+    /// The paths of the pages left out, with why they were.
+    pub excluded: BTreeMap<String, String>,
+}
+
+impl Profiles {
+    pub fn new(active: &[String], drafts: bool) -> Self {
+        Self {
+            active: active.to_vec(),
+            drafts,
+            excluded: BTreeMap::new(),
+        }
+    }
+
+    pub fn apply(&mut self, site: &Site) -> Result<Site, StandardError> {
+        let mut site = site.clone();
+
+        for snippet in &mut site.snippets {
+            snippet.markdown_contents = self.apply_sections(&snippet.markdown_contents).map_err(
+                |(line, text, message, help)| {
+                    let (pos_start, pos_end) = snippet.contents_range(line, &text);
+
+                    StandardError::new(&message, pos_start, pos_end, Some(&help))
+                },
+            )?;
+        }
+
+        site.pages = self.apply_pages(&site.pages)?;
+
+        Ok(site)
+    }
+
+    fn apply_pages(&mut self, pages: &[Page]) -> Result<Vec<Page>, StandardError> {
+        let mut kept = Vec::new();
+
+        for page in pages {
+            let reason = match &page.condition {
+                _ if page.draft && !self.drafts => {
+                    Some("the page is a draft, which is only built with --drafts".to_string())
+                }
+                Some(condition) if !self.matches(condition, || page.condition_range())? => {
+                    Some(format!(
+                        "the page is only built when '{condition}', put the link in an ':::if {condition}' section"
+                    ))
+                }
+                _ => None,
+            };
+
+            if let Some(reason) = reason {
+                for excluded in page.iter() {
+                    self.excluded.insert(excluded.path.clone(), reason.clone());
+                }
+
+                continue;
+            }
+
+            let mut page = page.clone();
+
+            page.markdown_contents = self.apply_sections(&page.markdown_contents).map_err(
+                |(line, text, message, help)| {
+                    let (pos_start, pos_end) = page.contents_range(line, &text);
+
+                    StandardError::new(&message, pos_start, pos_end, Some(&help))
+                },
+            )?;
+            page.subpages = self.apply_pages(&page.subpages)?;
+            kept.push(page);
+        }
+
+        Ok(kept)
+    }
+
+    fn matches(
+        &self,
+        condition: &str,
+        range: impl Fn() -> (Position, Position),
+    ) -> Result<bool, StandardError> {
+        Condition::parse(condition)
+            .map(|condition| condition.matches(&self.active))
+            .map_err(|(text, help)| {
+                let (pos_start, pos_end) = range();
+
+                StandardError::new(&text, pos_start, pos_end, Some(&help))
+            })
+    }
+
+    /// Blanks out the lines of sections that don't match, along with the lines of the
+    /// markers, so every other line stays where it was written and errors found later
+    /// still point at the right line. Returns the line, text, message and help of a
+    /// malformed section.
+    fn apply_sections(&self, contents: &str) -> Result<String, (usize, String, String, String)> {
+        if !contents.contains(SECTION_START) {
+            return Ok(contents.to_string());
+        }
+
+        let lines = contents.split('\n').collect::<Vec<_>>();
+        let mut output = Vec::new();
+        let mut frames: Vec<Frame> = Vec::new();
+        let mut fence: Option<&str> = None;
+
+        for (index, line) in lines.iter().enumerate() {
+            let trimmed = line.trim();
+            let parent_visible = frames.iter().all(|frame| frame.visible);
+            let mut keep = parent_visible;
+
+            if let Some(marker) = fence {
+                if trimmed.starts_with(marker) {
+                    fence = None;
+                }
+            } else if let Some(marker) = ["```", "~~~"]
+                .into_iter()
+                .find(|marker| trimmed.starts_with(marker))
+            {
+                fence = Some(marker);
+            } else if let Some(condition) = trimmed
+                .strip_prefix(SECTION_START)
+                .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+            {
+                let condition = Condition::parse(condition)
+                    .map_err(|(message, help)| (index, trimmed.to_string(), message, help))?;
+
+                frames.push(Frame {
+                    is_section: true,
+                    visible: condition.matches(&self.active),
+                    has_else: false,
+                    line: index,
+                });
+                keep = false;
+            } else if trimmed == SECTION_ELSE {
+                match frames.last_mut() {
+                    Some(frame) if frame.is_section && !frame.has_else => {
+                        frame.visible = !frame.visible;
+                        frame.has_else = true;
+                        keep = false;
+                    }
+                    Some(frame) if frame.is_section => {
+                        return Err((
+                            index,
+                            trimmed.to_string(),
+                            format!("second '{SECTION_ELSE}' in one '{SECTION_START}' section"),
+                            format!("a section has at most one '{SECTION_ELSE}'"),
+                        ));
+                    }
+                    _ => {
+                        return Err((
+                            index,
+                            trimmed.to_string(),
+                            format!("'{SECTION_ELSE}' outside of an '{SECTION_START}' section"),
+                            format!("start the section with '{SECTION_START} profile'"),
+                        ));
+                    }
+                }
+            } else if trimmed == SECTION_END {
+                if let Some(frame) = frames.pop()
+                    && frame.is_section
+                {
+                    keep = false;
+                }
+            } else if trimmed.starts_with(SECTION_END) {
+                frames.push(Frame {
+                    is_section: false,
+                    visible: true,
+                    has_else: false,
+                    line: index,
+                });
+            }
+
+            output.push(if keep { *line } else { "" });
+        }
+
+        if let Some(frame) = frames.iter().find(|frame| frame.is_section) {
+            return Err((
+                frame.line,
+                lines[frame.line].trim().to_string(),
+                format!("unclosed '{SECTION_START}' section"),
+                format!("end it with a line containing only '{SECTION_END}'"),
+            ));
+        }
+
+        Ok(output.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Profiles;
+    use crate::parse_str;
+
+    const SITE: &str = r#"
+        page {
+            name: "Home"
+            path: "/"
+            contents: "
+                Intro
+                :::if internal
+                Use the staging credentials.
+                :::else
+                Ask for access.
+                :::
+                Outro
+            "
+
+            page { name: "Internal" path: "/internal" contents: "" when: "internal" }
+            page { name: "Draft" path: "/draft" contents: "" draft: true }
+        }
+    "#;
+
+    fn apply(active: &[&str], drafts: bool) -> (Profiles, Vec<String>, String) {
+        let site = parse_str("test.dl", SITE).unwrap();
+        let active = active
+            .iter()
+            .map(|profile| profile.to_string())
+            .collect::<Vec<_>>();
+        let mut profiles = Profiles::new(&active, drafts);
+        let applied = profiles.apply(&site).unwrap();
+        let paths = applied.iter().map(|page| page.path.clone()).collect();
+        let contents = applied.pages[0]
+            .markdown_contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        (profiles, paths, contents)
+    }
+
+    #[test]
+    fn pages_are_left_out_unless_their_profile_is_active() {
+        let (profiles, paths, _) = apply(&[], false);
+
+        assert_eq!(paths, ["/"]);
+        assert!(profiles.excluded["/internal"].contains("only built when 'internal'"));
+        assert!(profiles.excluded["/draft"].contains("--drafts"));
+
+        let (profiles, paths, _) = apply(&["internal"], true);
+
+        assert_eq!(paths, ["/", "/internal", "/draft"]);
+        assert!(profiles.excluded.is_empty());
+    }
+
+    #[test]
+    fn sections_follow_the_active_profiles() {
+        let (_, _, contents) = apply(&["internal"], false);
+
+        assert_eq!(contents, "Intro\nUse the staging credentials.\nOutro");
+
+        let (_, _, contents) = apply(&[], false);
+
+        assert_eq!(contents, "Intro\nAsk for access.\nOutro");
+    }
+
+    #[test]
+    fn blanked_sections_keep_the_line_numbers() {
+        let profiles = Profiles::new(&[], false);
+        let contents = "a\n:::if internal\nb\n:::\nc";
+
+        assert_eq!(profiles.apply_sections(contents).unwrap(), "a\n\n\n\nc");
+    }
+
+    #[test]
+    fn malformed_sections_are_errors() {
+        let profiles = Profiles::new(&[], false);
+        let error = |contents: &str| profiles.apply_sections(contents).unwrap_err();
+
+        assert_eq!(error("a\n:::if internal\nb").2, "unclosed ':::if' section");
+        assert_eq!(error("a\n:::if internal\nb").0, 1);
+        assert_eq!(
+            error(":::if a\n:::else\n:::else\n:::").2,
+            "second ':::else' in one ':::if' section"
+        );
+        assert_eq!(
+            error(":::note\n:::else\n:::\n:::if a\n:::").2,
+            "':::else' outside of an ':::if' section"
+        );
+    }
+
+    #[test]
+    fn sections_in_code_blocks_are_kept() {
+        let profiles = Profiles::new(&[], false);
+        let contents = ":::if a\n:::\n```\n:::if internal\n```";
+
+        assert_eq!(
+            profiles.apply_sections(contents).unwrap(),
+            "\n\n```\n:::if internal\n```"
+        );
+    }
+}
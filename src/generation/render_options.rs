@@ -0,0 +1,83 @@
+use std::path::{Path, PathBuf};
+
+/// The kinds of output the generator can write for each page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// A static `index.html` per page, rendered through the page template.
+    Html,
+    /// An `index.json` document per page plus a `site.json` manifest.
+    Json,
+}
+
+/// Settings for turning a [`Site`](crate::Site) into output files.
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    pub(crate) output_dir: PathBuf,
+    pub(crate) formats: Vec<OutputFormat>,
+    pub(crate) templates_dir: Option<PathBuf>,
+    pub(crate) profiles: Vec<String>,
+    pub(crate) drafts: bool,
+}
+
+impl RenderOptions {
+    pub fn new(output_dir: impl AsRef<Path>) -> Self {
+        Self {
+            output_dir: output_dir.as_ref().to_path_buf(),
+            formats: vec![OutputFormat::Html],
+            templates_dir: None,
+            profiles: Vec::new(),
+            drafts: false,
+        }
+    }
+
+    /// Chooses which outputs are written. Defaults to HTML only.
+    pub fn with_formats(mut self, formats: &[OutputFormat]) -> Self {
+        self.formats = formats.to_vec();
+
+        self
+    }
+
+    /// Overrides the built in templates with the ones in `templates_dir`. Takes precedence
+    /// over the `templates` setting of the site.
+    pub fn with_templates_dir(mut self, templates_dir: impl AsRef<Path>) -> Self {
+        self.templates_dir = Some(templates_dir.as_ref().to_path_buf());
+
+        self
+    }
+
+    /// Activates a build profile, which decides the pages and sections with a matching
+    /// condition. Several profiles can be active at once.
+    pub fn with_profile(mut self, profile: &str) -> Self {
+        self.profiles.push(profile.to_string());
+
+        self
+    }
+
+    /// Builds draft pages too, and marks pages that are drafts, hidden or unlisted with a
+    /// banner, for previewing the site before it's published.
+    pub fn with_drafts(mut self, drafts: bool) -> Self {
+        self.drafts = drafts;
+
+        self
+    }
+
+    pub fn output_dir(&self) -> &Path {
+        &self.output_dir
+    }
+
+    pub fn formats(&self) -> &[OutputFormat] {
+        &self.formats
+    }
+
+    pub fn templates_dir(&self) -> Option<&Path> {
+        self.templates_dir.as_deref()
+    }
+
+    pub fn profiles(&self) -> &[String] {
+        &self.profiles
+    }
+
+    pub fn drafts(&self) -> bool {
+        self.drafts
+    }
+}
@@ -0,0 +1,176 @@
+use crate::{generation::markdown::RenderedMarkdown, parsing::page::Page};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// How much of the text of each page is kept for snippets, a couple of the 120 character
+/// windows `search.js` shows. Matches past it still rank the page, they just can't be shown
+/// in context. At 1500 the snippets made up over a third of the index of a large site.
+const SNIPPET_TEXT_LENGTH: usize = 300;
+const TITLE_WEIGHT: u32 = 10;
+const HEADING_WEIGHT: u32 = 5;
+const BODY_WEIGHT: u32 = 1;
+
+/// The search index written to `search-index.json`, read by `search.js` in the browser.
+///
+/// Terms are stored once in sorted order so the client can find every term starting with
+/// a prefix by binary search, and each term lists the documents it occurs in as flat
+/// `[document, score, document, score, ...]` pairs to keep the file small.
+#[derive(Debug, Default, Serialize)]
+pub struct SearchIndex {
+    #[serde(rename = "d")]
+    pub documents: Vec<SearchDocument>,
+    #[serde(rename = "t")]
+    pub terms: Vec<(String, Vec<u32>)>,
+    #[serde(skip)]
+    pub postings: BTreeMap<String, BTreeMap<u32, u32>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchDocument {
+    #[serde(rename = "t")]
+    pub title: String,
+    #[serde(rename = "u")]
+    pub href: String,
+    #[serde(rename = "x")]
+    pub text: String,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_page(&mut self, page: &Page, href: String, rendered: &RenderedMarkdown) {
+        let document = self.documents.len() as u32;
+
+        self.add_terms(document, &page.name, TITLE_WEIGHT);
+
+        for heading in &rendered.headings {
+            self.add_terms(document, &heading.text, HEADING_WEIGHT);
+        }
+
+        self.add_terms(document, &rendered.text, BODY_WEIGHT);
+
+        self.documents.push(SearchDocument {
+            title: page.name.clone(),
+            href,
+            text: rendered.text.chars().take(SNIPPET_TEXT_LENGTH).collect(),
+        });
+    }
+
+    fn add_terms(&mut self, document: u32, text: &str, weight: u32) {
+        for term in tokenize(text) {
+            *self
+                .postings
+                .entry(term)
+                .or_default()
+                .entry(document)
+                .or_default() += weight;
+        }
+    }
+
+    pub fn into_json(mut self) -> String {
+        self.terms = self
+            .postings
+            .iter()
+            .map(|(term, documents)| {
+                let flat = documents
+                    .iter()
+                    .flat_map(|(document, score)| [*document, *score])
+                    .collect();
+
+                (term.clone(), flat)
+            })
+            .collect();
+
+        serde_json::to_string(&self).expect("Error serializing search index")
+    }
+}
+
+/// Splits text into lowercase words the same way `search.js` splits queries.
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|character: char| !character.is_alphanumeric())
+        .filter(|word| word.chars().count() > 1)
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SNIPPET_TEXT_LENGTH, SearchIndex, tokenize};
+    use crate::{
+        generation::markdown::{MarkdownOptions, render_markdown},
+        parsing::page::Page,
+    };
+
+    #[test]
+    fn words_are_lowercased_and_split_on_punctuation() {
+        let terms = tokenize("Install the CLI: run `dl-build`, a 2nd time.").collect::<Vec<_>>();
+
+        assert_eq!(
+            terms,
+            ["install", "the", "cli", "run", "dl", "build", "2nd", "time"]
+        );
+    }
+
+    #[test]
+    fn titles_and_headings_weigh_more_than_text() {
+        let mut index = SearchIndex::new();
+        let page = Page::new("Deploy", "/deploy", "", vec![]);
+        let rendered = render_markdown(
+            "## Servers\n\nDeploy to your servers.",
+            &MarkdownOptions::default(),
+        );
+
+        index.add_page(&page, "/deploy/".to_string(), &rendered);
+
+        assert_eq!(index.postings["deploy"][&0], 10 + 1);
+        assert_eq!(index.postings["servers"][&0], 5 + 1 + 1);
+        assert_eq!(index.postings["your"][&0], 1);
+    }
+
+    #[test]
+    fn the_json_lists_documents_and_flat_postings() {
+        let mut index = SearchIndex::new();
+
+        for (name, path) in [("Install", "/install"), ("Install faq", "/faq")] {
+            let page = Page::new(name, path, "", vec![]);
+            index.add_page(
+                &page,
+                format!("{path}/"),
+                &render_markdown("Some text.", &MarkdownOptions::default()),
+            );
+        }
+
+        let json: serde_json::Value = serde_json::from_str(&index.into_json()).unwrap();
+
+        assert_eq!(json["d"][1]["t"], "Install faq");
+        assert_eq!(json["d"][1]["u"], "/faq/");
+        assert_eq!(json["d"][0]["x"], "Some text.");
+
+        let terms = json["t"].as_array().unwrap();
+        let install = terms.iter().find(|term| term[0] == "install").unwrap();
+
+        assert_eq!(install[1], serde_json::json!([0, 10, 1, 10]));
+        assert!(
+            terms
+                .windows(2)
+                .all(|pair| pair[0][0].as_str() < pair[1][0].as_str())
+        );
+    }
+
+    #[test]
+    fn only_the_start_of_the_text_is_kept_for_snippets() {
+        let mut index = SearchIndex::new();
+        let page = Page::new("Long", "/long", "", vec![]);
+        let text = format!("{} needle", "word ".repeat(200));
+
+        index.add_page(
+            &page,
+            "/long/".to_string(),
+            &render_markdown(&text, &MarkdownOptions::default()),
+        );
+
+        assert_eq!(index.documents[0].text.chars().count(), SNIPPET_TEXT_LENGTH);
+        assert!(index.postings.contains_key("needle"));
+    }
+}
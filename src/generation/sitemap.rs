@@ -0,0 +1,111 @@
+use crate::generation::{
+    generator::{NOT_FOUND_PATH, page_href},
+    markdown::escape_html,
+    outline::Outline,
+};
+
+/// The `sitemap.xml` of a site, listing the pages search engines should crawl in reading
+/// order. Hidden, unlisted and draft pages are left out, along with the not found page.
+#[derive(Debug, Default)]
+pub struct Sitemap {
+    pub entries: Vec<SitemapEntry>,
+}
+
+#[derive(Debug)]
+pub struct SitemapEntry {
+    /// The absolute URL of the page.
+    pub loc: String,
+    /// The date of the page, when it has one.
+    pub lastmod: Option<String>,
+}
+
+impl Sitemap {
+    pub fn new(outline: &Outline, site_url: &str) -> Self {
+        let site_url = site_url.trim_end_matches('/');
+        let entries = outline
+            .entries
+            .iter()
+            .filter(|entry| {
+                !entry.hidden()
+                    && !entry.page.unlisted
+                    && !entry.page.draft
+                    && entry.page.path.trim_end_matches('/') != NOT_FOUND_PATH
+            })
+            .map(|entry| SitemapEntry {
+                loc: format!("{site_url}{}", page_href(&entry.page.path)),
+                lastmod: entry.page.date.clone(),
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    pub fn into_xml(self) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+        );
+
+        for entry in self.entries {
+            xml.push_str(&format!(
+                "    <url>\n        <loc>{}</loc>\n",
+                escape_html(&entry.loc)
+            ));
+
+            if let Some(lastmod) = entry.lastmod {
+                xml.push_str(&format!("        <lastmod>{lastmod}</lastmod>\n"));
+            }
+
+            xml.push_str("    </url>\n");
+        }
+
+        xml.push_str("</urlset>\n");
+
+        xml
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sitemap;
+    use crate::{generation::outline::Outline, parse_str};
+
+    #[test]
+    fn only_crawlable_pages_are_listed() {
+        let site = parse_str(
+            "test.dl",
+            r#"
+            page {
+                name: "Home"
+                path: "/"
+                contents: ""
+                date: "2026-05-01"
+
+                page { name: "Hidden" path: "/hidden" contents: "" hidden: true
+                    page { name: "Under hidden" path: "/hidden/child" contents: "" }
+                }
+                page { name: "Unlisted" path: "/unlisted" contents: "" unlisted: true }
+                page { name: "Draft" path: "/draft" contents: "" draft: true }
+                page { name: "Q&A" path: "/q&a" contents: "" }
+            }
+            page { name: "Not found" path: "/404" contents: "" }
+            "#,
+        )
+        .unwrap();
+        let xml = Sitemap::new(&Outline::new(&site), "https://docs.example.com/").into_xml();
+        let locs = xml
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("<loc>"))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            locs,
+            [
+                "https://docs.example.com/</loc>",
+                "https://docs.example.com/q&amp;a/</loc>",
+            ]
+        );
+        assert!(xml.contains("<lastmod>2026-05-01</lastmod>"));
+        assert_eq!(xml.matches("<lastmod>").count(), 1);
+    }
+}
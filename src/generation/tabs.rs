@@ -0,0 +1,225 @@
+use crate::generation::{
+    headings::{slugify, unique_id},
+    markdown::escape_html,
+};
+use std::collections::HashSet;
+
+/// One `::tab Label` section of a tab group and the lines under it, with their index.
+#[derive(Debug, Clone)]
+struct Tab<'a> {
+    label: &'a str,
+    lines: Vec<(usize, &'a str)>,
+}
+
+/// A line of a tab group before its first `::tab`, which no tab holds so it isn't shown.
+#[derive(Debug, Clone)]
+pub struct StrayTabLine {
+    /// The line of the dedented contents it's on.
+    pub line: usize,
+    pub text: String,
+}
+
+/// Turns `:::tabs key` groups into stacked panels with a heading each, which `tabs.js`
+/// turns into a tab widget. Without JavaScript every tab stays visible, one after another.
+///
+/// ```text
+/// :::tabs os
+/// ::tab Linux
+/// apt install dl
+/// ::tab macOS
+/// brew install dl
+/// :::
+/// ```
+pub fn expand_tabs(markdown: &str) -> String {
+    let lines = markdown.lines().enumerate().collect::<Vec<_>>();
+
+    expand_groups(&lines, &mut 0, &mut Vec::new())
+}
+
+/// Finds the lines of tab groups that come before their first tab, to point them out
+/// instead of silently dropping them.
+pub fn find_stray_tab_lines(markdown: &str) -> Vec<StrayTabLine> {
+    let lines = markdown.lines().enumerate().collect::<Vec<_>>();
+    let mut stray = Vec::new();
+
+    expand_groups(&lines, &mut 0, &mut stray);
+    stray
+}
+
+fn expand_groups(
+    lines: &[(usize, &str)],
+    group_count: &mut usize,
+    stray: &mut Vec<StrayTabLine>,
+) -> String {
+    let mut output = String::new();
+    let mut fence: Option<&str> = None;
+    let mut index = 0;
+
+    while index < lines.len() {
+        let (_, line) = lines[index];
+        let trimmed = line.trim();
+        index += 1;
+
+        if let Some(marker) = fence {
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+        } else if let Some(marker) = fence_marker(trimmed) {
+            fence = Some(marker);
+        } else if let Some(key) = trimmed.strip_prefix(":::tabs") {
+            let mut tabs: Vec<Tab> = Vec::new();
+            let mut depth = 1;
+            // markers in code blocks of a tab are part of the code
+            let mut tab_fence: Option<&str> = None;
+
+            while let Some(&(line_index, line)) = lines.get(index) {
+                let trimmed = line.trim();
+                index += 1;
+
+                if let Some(marker) = tab_fence {
+                    if trimmed.starts_with(marker) {
+                        tab_fence = None;
+                    }
+                } else if let Some(marker) = fence_marker(trimmed) {
+                    tab_fence = Some(marker);
+                } else if trimmed == ":::" {
+                    depth -= 1;
+
+                    if depth == 0 {
+                        break;
+                    }
+                } else if trimmed.starts_with(":::") {
+                    depth += 1;
+                } else if depth == 1
+                    && let Some(label) = trimmed.strip_prefix("::tab ")
+                {
+                    tabs.push(Tab {
+                        label: label.trim(),
+                        lines: Vec::new(),
+                    });
+                    continue;
+                }
+
+                match tabs.last_mut() {
+                    Some(tab) => tab.lines.push((line_index, line)),
+                    None if !trimmed.is_empty() => stray.push(StrayTabLine {
+                        line: line_index,
+                        text: trimmed.to_string(),
+                    }),
+                    None => {}
+                }
+            }
+
+            *group_count += 1;
+            output.push_str(&render_group(key.trim(), &tabs, group_count, stray));
+            continue;
+        }
+
+        output.push_str(line);
+        output.push('\n');
+    }
+
+    output
+}
+
+fn fence_marker(trimmed: &str) -> Option<&'static str> {
+    ["```", "~~~"]
+        .into_iter()
+        .find(|marker| trimmed.starts_with(marker))
+}
+
+fn render_group(
+    key: &str,
+    tabs: &[Tab],
+    group_count: &mut usize,
+    stray: &mut Vec<StrayTabLine>,
+) -> String {
+    let group = *group_count;
+    let mut html = String::from("<div class=\"tabs\"");
+    let mut used_names = HashSet::new();
+
+    if !key.is_empty() {
+        html.push_str(&format!(" data-tab-key=\"{}\"", escape_html(key)));
+    }
+
+    html.push_str(">\n\n");
+
+    for tab in tabs {
+        // tabs with the same label are numbered, so that each can still be selected
+        let name = unique_id(&slugify(tab.label), &mut used_names);
+        let body = expand_groups(&tab.lines, group_count, stray);
+
+        html.push_str(&format!(
+            "<section class=\"tab-panel\" id=\"tabs-{group}-{name}\" data-tab=\"{name}\">\n<h4 class=\"tab-title\">{}</h4>\n\n{body}\n\n</section>\n\n",
+            escape_html(tab.label)
+        ));
+    }
+
+    html.push_str("</div>\n\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_tabs, find_stray_tab_lines};
+
+    const GROUP: &str =
+        ":::tabs os\n::tab Linux\napt install dl\n::tab macOS\nbrew install dl\n:::\nAfter";
+
+    #[test]
+    fn tabs_become_panels() {
+        let html = expand_tabs(GROUP);
+
+        assert!(html.starts_with("<div class=\"tabs\" data-tab-key=\"os\">"));
+        assert!(html.contains("id=\"tabs-1-linux\" data-tab=\"linux\""));
+        assert!(html.contains("<h4 class=\"tab-title\">macOS</h4>\n\nbrew install dl\n"));
+        assert!(html.ends_with("</div>\n\nAfter\n"));
+    }
+
+    #[test]
+    fn groups_are_numbered_and_nest() {
+        let markdown =
+            ":::tabs\n::tab One\n:::tabs\n::tab Inner\nText\n:::\n:::\n\n:::tabs\n::tab Two\n:::";
+        let html = expand_tabs(markdown);
+
+        assert!(html.starts_with("<div class=\"tabs\">"));
+        assert!(html.contains("id=\"tabs-1-one\""));
+        assert!(html.contains("id=\"tabs-2-inner\""));
+        assert!(html.contains("id=\"tabs-3-two\""));
+    }
+
+    #[test]
+    fn groups_in_code_blocks_are_left_alone() {
+        let markdown = "```\n:::tabs\n::tab Linux\n:::\n```\n";
+
+        assert_eq!(expand_tabs(markdown), markdown);
+    }
+
+    #[test]
+    fn markers_in_code_blocks_stay_in_their_tab() {
+        let markdown = ":::tabs\n::tab Shell\n```\n:::\n::tab Not a tab\n```\n::tab Other\n:::";
+        let html = expand_tabs(markdown);
+
+        assert_eq!(html.matches("<section").count(), 2);
+        assert!(html.contains("```\n:::\n::tab Not a tab\n```"));
+    }
+
+    #[test]
+    fn lines_before_the_first_tab_are_reported() {
+        let markdown = "Intro\n:::tabs\nLost\n\n::tab One\nKept\n:::";
+        let stray = find_stray_tab_lines(markdown);
+
+        assert_eq!(stray.len(), 1);
+        assert_eq!(stray[0].line, 2);
+        assert_eq!(stray[0].text, "Lost");
+        assert!(find_stray_tab_lines(GROUP).is_empty());
+    }
+
+    #[test]
+    fn tabs_with_the_same_label_get_their_own_id() {
+        let html = expand_tabs(":::tabs\n::tab Linux\nOne\n::tab Linux\nTwo\n:::");
+
+        assert!(html.contains("id=\"tabs-1-linux\" data-tab=\"linux\""));
+        assert!(html.contains("id=\"tabs-1-linux-1\" data-tab=\"linux-1\""));
+    }
+}
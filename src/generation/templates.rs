@@ -0,0 +1,217 @@
+use crate::{errors::standard_error::StandardError, lexing::position::Position};
+use minijinja::{Environment, Error, ErrorKind, Value};
+use std::{fs, path::PathBuf};
+
+/// The templates compiled into the binary, used whenever no override exists.
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    ("base.html", include_str!("../../templates/base.html")),
+    (
+        "redirect.html",
+        include_str!("../../templates/redirect.html"),
+    ),
+    (
+        "partials/head.html",
+        include_str!("../../templates/partials/head.html"),
+    ),
+    (
+        "partials/meta.html",
+        include_str!("../../templates/partials/meta.html"),
+    ),
+    (
+        "partials/navigation.html",
+        include_str!("../../templates/partials/navigation.html"),
+    ),
+    (
+        "partials/breadcrumbs.html",
+        include_str!("../../templates/partials/breadcrumbs.html"),
+    ),
+    (
+        "partials/page_states.html",
+        include_str!("../../templates/partials/page_states.html"),
+    ),
+    (
+        "partials/pagination.html",
+        include_str!("../../templates/partials/pagination.html"),
+    ),
+    (
+        "partials/toc.html",
+        include_str!("../../templates/partials/toc.html"),
+    ),
+    (
+        "partials/search.html",
+        include_str!("../../templates/partials/search.html"),
+    ),
+    (
+        "layouts/default.html",
+        include_str!("../../templates/layouts/default.html"),
+    ),
+    (
+        "layouts/landing.html",
+        include_str!("../../templates/layouts/landing.html"),
+    ),
+    (
+        "layouts/reference.html",
+        include_str!("../../templates/layouts/reference.html"),
+    ),
+    (
+        "layouts/changelog.html",
+        include_str!("../../templates/layouts/changelog.html"),
+    ),
+];
+
+pub const DEFAULT_LAYOUT: &str = "default";
+
+pub struct Templates {
+    pub environment: Environment<'static>,
+    pub override_dir: Option<PathBuf>,
+}
+
+impl Templates {
+    /// Loads templates from `override_dir` when a file with the same name exists there,
+    /// falling back to the built in defaults otherwise.
+    pub fn new(override_dir: Option<PathBuf>) -> Self {
+        let mut environment = Environment::new();
+        environment.set_keep_trailing_newline(true);
+
+        let loader_dir = override_dir.clone();
+        environment.set_loader(move |name| {
+            if let Some(dir) = &loader_dir {
+                let path = dir.join(name);
+
+                if path.is_file() {
+                    return fs::read_to_string(&path).map(Some).map_err(|e| {
+                        Error::new(
+                            ErrorKind::InvalidOperation,
+                            format!("could not read template '{}'", path.display()),
+                        )
+                        .with_source(e)
+                    });
+                }
+            }
+
+            Ok(BUILTIN_TEMPLATES
+                .iter()
+                .find(|(builtin_name, _)| *builtin_name == name)
+                .map(|(_, source)| source.to_string()))
+        });
+
+        Self {
+            environment,
+            override_dir,
+        }
+    }
+
+    /// The names of every layout a page can pick: the built in ones plus any `.html` file
+    /// in the `layouts` directory of the template overrides, sorted.
+    pub fn layouts(&self) -> Vec<String> {
+        let mut layouts = BUILTIN_TEMPLATES
+            .iter()
+            .filter_map(|(name, _)| name.strip_prefix("layouts/"))
+            .filter_map(|name| name.strip_suffix(".html"))
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+
+        if let Some(entries) = self
+            .override_dir
+            .as_ref()
+            .and_then(|dir| fs::read_dir(dir.join("layouts")).ok())
+        {
+            for entry in entries.flatten() {
+                let path = entry.path();
+
+                if path
+                    .extension()
+                    .is_some_and(|extension| extension == "html")
+                    && let Some(name) = path.file_stem().and_then(|stem| stem.to_str())
+                {
+                    layouts.push(name.to_string());
+                }
+            }
+        }
+
+        layouts.sort();
+        layouts.dedup();
+        layouts
+    }
+
+    pub fn render(&self, name: &str, context: Value) -> Result<String, StandardError> {
+        self.environment
+            .get_template(name)
+            .and_then(|template| template.render(context))
+            .map_err(template_error)
+    }
+}
+
+/// Converts a template error into a `StandardError` pointing at the line of the template
+/// that caused it.
+fn template_error(error: Error) -> StandardError {
+    let filename = error.name().unwrap_or("<template>").to_string();
+    let source = error.template_source().unwrap_or("").to_string();
+    let line_num = error.line().unwrap_or(1) as isize - 1;
+
+    let (pos_start, pos_end) = match error.range() {
+        Some(range) => (
+            position_at(&filename, &source, range.start),
+            position_at(&filename, &source, range.end),
+        ),
+        None => {
+            let pos_start = Position::new(0, line_num, 0, &filename, &source);
+            let mut pos_end = pos_start.clone();
+            pos_end.advance(None);
+
+            (pos_start, pos_end)
+        }
+    };
+
+    StandardError::new(
+        &format!("template error: {}", error.kind()),
+        pos_start,
+        pos_end,
+        error.detail(),
+    )
+}
+
+fn position_at(filename: &str, source: &str, index: usize) -> Position {
+    let before = &source[..index.min(source.len())];
+    let line_num = before.matches('\n').count() as isize;
+    let column_num = before
+        .rsplit('\n')
+        .next()
+        .map(|line| line.chars().count())
+        .unwrap_or(0) as isize;
+
+    Position::new(index as isize, line_num, column_num, filename, source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Templates;
+    use minijinja::context;
+
+    #[test]
+    fn changelog_lists_children_newest_first() {
+        let html = Templates::new(None)
+            .render(
+                "layouts/changelog.html",
+                context! {
+                    page => context! { title => "Changelog", date => "2026-03-01" },
+                    meta => context! {},
+                    trail => context! { breadcrumbs => Vec::<()>::new() },
+                    navigation => Vec::<()>::new(),
+                    toc => Vec::<()>::new(),
+                    children => vec![
+                        context! { title => "1.0", href => "/1-0/", date => "2026-01-01" },
+                        context! { title => "Notes", href => "/notes/" },
+                        context! { title => "2.0", href => "/2-0/", date => "2026-03-01" },
+                    ],
+                },
+            )
+            .unwrap();
+        let position = |text: &str| html.find(text).unwrap();
+
+        assert!(html.contains("<time class=\"changelog-date\" datetime=\"2026-03-01\">"));
+        assert!(position(">2.0<") < position(">1.0<"));
+        assert!(position(">1.0<") < position(">Notes<"));
+        assert!(html.contains("<time datetime=\"2026-01-01\">2026-01-01</time>"));
+    }
+}
@@ -6,8 +6,6 @@ use crate::syntax::attributes::*;
 use std::sync::Arc;
 
 pub struct Lexer {
-    pub filename: String,
-    pub text: String,
     pub chars: Arc<[char]>,
     pub position: Position,
     pub current_char: Option<char>,
@@ -18,8 +16,6 @@ impl Lexer {
         let contents = text.replace("\r\n", "\n");
 
         let mut lexer = Self {
-            filename: filename.to_string(),
-            text: contents.to_string(),
             chars: contents.chars().collect::<Vec<_>>().into(),
             position: Position::new(-1, 0, -1, filename, &contents.clone()),
             current_char: None,
@@ -39,6 +35,10 @@ impl Lexer {
         }
     }
 
+    pub fn peek(&self) -> Option<char> {
+        self.chars.get((self.position.index + 1) as usize).copied()
+    }
+
     pub fn make_tokens(&mut self) -> Result<Vec<Token>, StandardError> {
         let mut tokens = Vec::new();
 
@@ -55,6 +55,8 @@ impl Lexer {
                     continue;
                 }
                 c if LETTERS.contains(c) => Some(self.make_identifier()),
+                c if DIGITS.contains(c) => Some(self.make_number()),
+                '-' if self.peek().is_some_and(|c| DIGITS.contains(c)) => Some(self.make_number()),
                 '"' => match self.make_string() {
                     Ok(token) => Some(token),
                     Err(error) => return Err(error),
@@ -89,13 +91,23 @@ impl Lexer {
                     self.advance();
                     Some(token)
                 }
+                '=' => {
+                    let token = Token::new(
+                        TokenType::TT_EQUALS,
+                        None,
+                        Some(self.position.clone()),
+                        None,
+                    );
+                    self.advance();
+                    Some(token)
+                }
                 unknown_char => {
                     let pos_start = self.position.clone();
 
                     self.advance();
 
                     return Err(StandardError::new(
-                        &format!("unkown character '{unknown_char}'"),
+                        &format!("unknown character '{unknown_char}'"),
                         pos_start,
                         self.position.clone(),
                         None,
@@ -143,6 +155,36 @@ impl Lexer {
         Token::new(token_type, Some(id_string), Some(pos_start), Some(pos_end))
     }
 
+    pub fn make_number(&mut self) -> Token {
+        let mut number_string = String::new();
+        let pos_start = self.position.clone();
+
+        if self.current_char == Some('-') {
+            number_string.push('-');
+
+            self.advance();
+        }
+
+        while let Some(character) = self.current_char {
+            if DIGITS.contains(character) {
+                number_string.push(character);
+
+                self.advance();
+            } else {
+                break;
+            }
+        }
+
+        let pos_end = self.position.clone();
+
+        Token::new(
+            TokenType::TT_NUMBER,
+            Some(number_string),
+            Some(pos_start),
+            Some(pos_end),
+        )
+    }
+
     pub fn make_string(&mut self) -> Result<Token, StandardError> {
         let mut string = String::new();
         let pos_start = self.position.clone();
@@ -154,6 +196,16 @@ impl Lexer {
                 break;
             }
 
+            if character == '\\'
+                && let Some(escaped @ ('"' | '\\')) = self.peek()
+            {
+                self.advance();
+                string.push(escaped);
+                self.advance();
+
+                continue;
+            }
+
             string.push(character);
 
             self.advance();
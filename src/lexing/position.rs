@@ -1,9 +1,12 @@
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Position {
     pub index: isize,
     pub line_num: isize,
     pub column_num: isize,
     pub filename: String,
+    #[serde(skip)]
     pub file_contents: String,
 }
 
@@ -28,13 +31,79 @@ impl Position {
         self.index += 1;
         self.column_num += 1;
 
-        if let Some(character) = current_char {
-            if character == '\n' {
-                self.line_num += 1;
-                self.column_num = 0;
-            }
+        if current_char == Some('\n') {
+            self.line_num += 1;
+            self.column_num = 0;
         }
 
         self.clone()
     }
+
+    /// The range of `text` on a line of the string token that starts at this position,
+    /// counting the line the string opens on as `0`. Falls back to the whole line when
+    /// `text` isn't found there.
+    pub(crate) fn range_in_string(&self, line: usize, text: &str) -> (Position, Position) {
+        let line_num = self.line_num as usize + line;
+        let mut line_index = 0;
+        let mut line_text = "";
+
+        for (index, source_line) in self.file_contents.split('\n').enumerate() {
+            if index == line_num {
+                line_text = source_line;
+                break;
+            }
+
+            line_index += source_line.chars().count() + 1;
+        }
+
+        // quotes are escaped in the source
+        let escaped = text.replace('"', "\\\"");
+        let (column, length) = match line_text.find(&escaped) {
+            Some(byte) => (line_text[..byte].chars().count(), escaped.chars().count()),
+            None => {
+                let indentation = line_text.len() - line_text.trim_start().len();
+                (indentation, line_text.trim().chars().count())
+            }
+        };
+        let pos_start = Position::new(
+            (line_index + column) as isize,
+            line_num as isize,
+            column as isize,
+            &self.filename,
+            &self.file_contents,
+        );
+        let mut pos_end = pos_start.clone();
+
+        for _ in 0..length.max(1) {
+            pos_end.advance(None);
+        }
+
+        (pos_start, pos_end)
+    }
+
+    /// The range of the first `text` in the file at or after this position.
+    pub(crate) fn find_after(&self, text: &str) -> Option<(Position, Position)> {
+        let start_byte = self
+            .file_contents
+            .char_indices()
+            .nth(self.index.max(0) as usize)
+            .map_or(self.file_contents.len(), |(byte, _)| byte);
+        let byte = start_byte + self.file_contents[start_byte..].find(text)?;
+        let before = &self.file_contents[..byte];
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        let pos_start = Position::new(
+            before.chars().count() as isize,
+            before.matches('\n').count() as isize,
+            before[line_start..].chars().count() as isize,
+            &self.filename,
+            &self.file_contents,
+        );
+        let mut pos_end = pos_start.clone();
+
+        for _ in 0..text.chars().count() {
+            pos_end.advance(None);
+        }
+
+        Some((pos_start, pos_end))
+    }
 }
@@ -34,10 +34,9 @@ impl Token {
     }
 
     pub fn matches(&self, token_type: TokenType, value: &str) -> bool {
-        if self.value.is_some() {
-            self.token_type == token_type && self.value.as_ref().unwrap() == value
-        } else {
-            false
+        match &self.value {
+            Some(own_value) => self.token_type == token_type && own_value == value,
+            None => false,
         }
     }
 }
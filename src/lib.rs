@@ -1,19 +1,42 @@
+mod building;
 mod errors;
 mod generation;
 mod lexing;
 mod parsing;
 mod syntax;
+mod validation;
+
+pub use crate::{
+    building::site_builder::SiteBuilder,
+    errors::{standard_error::StandardError, standard_warning::StandardWarning},
+    generation::render_options::{OutputFormat, RenderOptions},
+    lexing::position::Position,
+    parsing::{
+        index_style::IndexStyle, markdown_extensions::MarkdownExtensions, page::Page,
+        parse_options::ParseOptions, redirect_outputs::RedirectOutputs, site::Site,
+        site_config::SiteConfig, snippet::Snippet, sort_order::SortOrder, variable::Variable,
+    },
+};
 use crate::{
-    errors::standard_error::StandardError, generation::generator::Generator, lexing::lexer::Lexer,
-    parsing::parser::Parser,
+    generation::{generator::Generator, profiles::Profiles},
+    lexing::lexer::Lexer,
+    parsing::{interpolator::Interpolator, node::Node, parser::Parser},
+    validation::validator::Validator,
 };
 
-pub fn generate_site(filename: &str, output_dir: &str, code: &str) -> Result<(), StandardError> {
+/// Parses `.dl` source into a [`Site`]. `filename` is only used for error reporting.
+pub fn parse_str(filename: &str, code: &str) -> Result<Site, StandardError> {
+    parse_str_with(filename, code, &ParseOptions::new())
+}
+
+/// Like [`parse_str`], with the variables of `options` overriding those of the file.
+pub fn parse_str_with(
+    filename: &str,
+    code: &str,
+    options: &ParseOptions,
+) -> Result<Site, StandardError> {
     let mut lexer = Lexer::new(filename, code.to_string());
-    let tokens = match lexer.make_tokens() {
-        Ok(tok) => tok,
-        Err(e) => return Err(e),
-    };
+    let tokens = lexer.make_tokens()?;
 
     let mut parser = Parser::new(&tokens);
     let parsed = parser.parse();
@@ -22,11 +45,39 @@ pub fn generate_site(filename: &str, output_dir: &str, code: &str) -> Result<(),
         return Err(err);
     }
 
-    let parsed_page = parsed.page.as_ref().unwrap().clone();
+    let mut site = match parsed.node {
+        Some(Node::Statements(statements)) => Site::from_statements(filename, statements)?,
+        _ => Site::from_statements(filename, Vec::new())?,
+    };
+
+    Interpolator::new(&site, options.variables())?.interpolate(&mut site)?;
+    Validator::new().validate(&site)?;
+
+    Ok(site)
+}
+
+/// Writes every page of `site` into the configured output directory, in each of the
+/// configured output formats, leaving out what the active profiles exclude. Returns the
+/// warnings found along the way.
+pub fn render(site: &Site, options: &RenderOptions) -> Result<Vec<StandardWarning>, StandardError> {
+    let mut profiles = Profiles::new(options.profiles(), options.drafts());
+    let mut site = profiles.apply(site)?;
+
+    site.sort_pages();
+    let mut generator = Generator::new(options, &site);
+
+    generator.excluded_pages = profiles.excluded;
+    generator.render_site(&site)?;
+
+    Ok(generator.warnings)
+}
 
-    let mut generator = Generator::new(output_dir);
-    generator.build_navigation(&parsed_page, 0);
-    generator.generate(&parsed_page);
+pub fn generate_site(
+    filename: &str,
+    output_dir: &str,
+    code: &str,
+) -> Result<Vec<StandardWarning>, StandardError> {
+    let site = parse_str(filename, code)?;
 
-    Ok(())
+    render(&site, &RenderOptions::new(output_dir))
 }
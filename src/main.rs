@@ -1,8 +1,10 @@
-use clap::{Parser, Subcommand};
-use dl::generate_site;
-use std::fs;
+use clap::{Parser, Subcommand, ValueEnum};
+use dl::{OutputFormat, ParseOptions, RenderOptions, Site, parse_str, parse_str_with, render};
+use std::{env, fs, path::Path};
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Environment variables starting with this set the variable named by the rest.
+const VAR_ENV_PREFIX: &str = "DL_VAR_";
 
 #[derive(Parser)]
 #[command(name = "dl", version = VERSION, about = "Generate documentation sites all from one file")]
@@ -13,27 +15,280 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
-    Build { file: String, outdir: String },
+    Build {
+        file: String,
+        outdir: String,
+        /// Which outputs to write, separated by commas
+        #[arg(
+            long = "format",
+            value_enum,
+            value_delimiter = ',',
+            default_value = "html"
+        )]
+        formats: Vec<BuildFormat>,
+        /// Directory of templates overriding the built in ones
+        #[arg(long)]
+        templates: Option<String>,
+        /// Build profiles to activate, separated by commas, e.g. internal
+        #[arg(long = "profile", value_delimiter = ',')]
+        profiles: Vec<String>,
+        /// Build draft pages and mark page states, for previewing
+        #[arg(long)]
+        drafts: bool,
+        /// Set a variable, overriding the file and DL_VAR_<name> environment variables
+        #[arg(long = "var", value_name = "NAME=VALUE", value_parser = parse_variable)]
+        variables: Vec<(String, String)>,
+    },
+    /// Print the parsed page tree as structured data
+    Dump {
+        file: String,
+        #[arg(long, value_enum, default_value_t = DataFormat::Json)]
+        format: DataFormat,
+        /// Set a variable, overriding the file and DL_VAR_<name> environment variables
+        #[arg(long = "var", value_name = "NAME=VALUE", value_parser = parse_variable)]
+        variables: Vec<(String, String)>,
+    },
+    /// Turn a page tree produced by `dump` back into `.dl` source
+    Load {
+        file: String,
+        /// Defaults to the format matching the file extension
+        #[arg(long, value_enum)]
+        format: Option<DataFormat>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum BuildFormat {
+    Html,
+    Json,
+}
+
+impl From<BuildFormat> for OutputFormat {
+    fn from(format: BuildFormat) -> Self {
+        match format {
+            BuildFormat::Html => OutputFormat::Html,
+            BuildFormat::Json => OutputFormat::Json,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum DataFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl DataFormat {
+    fn from_extension(file: &str) -> Option<Self> {
+        match Path::new(file).extension()?.to_str()? {
+            "json" => Some(Self::Json),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+
+    fn serialize(self, site: &Site) -> Result<String, String> {
+        match self {
+            Self::Json => serde_json::to_string_pretty(site).map_err(|e| e.to_string()),
+            Self::Yaml => serde_yaml::to_string(site).map_err(|e| e.to_string()),
+            Self::Toml => toml::to_string_pretty(site).map_err(|e| e.to_string()),
+        }
+    }
+
+    fn deserialize(self, text: &str) -> Result<Site, String> {
+        match self {
+            Self::Json => serde_json::from_str(text).map_err(|e| e.to_string()),
+            Self::Yaml => serde_yaml::from_str(text).map_err(|e| e.to_string()),
+            Self::Toml => toml::from_str(text).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+fn parse_variable(argument: &str) -> Result<(String, String), String> {
+    match argument.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => {
+            Ok((name.trim().to_string(), value.to_string()))
+        }
+        _ => Err(format!("expected NAME=VALUE, got '{}'", argument)),
+    }
+}
+
+/// Variables from the environment, then from `--var`, which take precedence.
+fn parse_options(
+    environment: impl Iterator<Item = (String, String)>,
+    variables: Vec<(String, String)>,
+) -> ParseOptions {
+    environment
+        .filter_map(|(key, value)| Some((key.strip_prefix(VAR_ENV_PREFIX)?.to_string(), value)))
+        .chain(variables)
+        .fold(ParseOptions::new(), |options, (name, value)| {
+            options.with_variable(&name, &value)
+        })
 }
 
 fn main() {
     let cli = Cli::parse();
 
     match cli.command {
-        Some(Commands::Build { file, outdir }) => {
-            match generate_site(
-                &file.to_owned(),
-                &outdir,
-                &fs::read_to_string(file).expect("Err"),
+        Some(Commands::Build {
+            file,
+            outdir,
+            formats,
+            templates,
+            profiles,
+            drafts,
+            variables,
+        }) => {
+            let formats = formats
+                .into_iter()
+                .map(OutputFormat::from)
+                .collect::<Vec<_>>();
+            let mut options = RenderOptions::new(&outdir)
+                .with_formats(&formats)
+                .with_drafts(drafts);
+
+            if let Some(templates) = templates {
+                options = options.with_templates_dir(templates);
+            }
+
+            for profile in &profiles {
+                options = options.with_profile(profile);
+            }
+
+            let parse_options = parse_options(env::vars(), variables);
+
+            match parse_str_with(
+                &file,
+                &fs::read_to_string(&file).expect("Err"),
+                &parse_options,
+            )
+            .and_then(|site| render(&site, &options))
+            {
+                Ok(warnings) => {
+                    for warning in warnings {
+                        println!("{}", warning);
+                    }
+                }
+                Err(e) => {
+                    println!("{}", e);
+                }
+            };
+        }
+        Some(Commands::Dump {
+            file,
+            format,
+            variables,
+        }) => {
+            let parse_options = parse_options(env::vars(), variables);
+
+            match parse_str_with(
+                &file,
+                &fs::read_to_string(&file).expect("Err"),
+                &parse_options,
             ) {
-                Ok(_) => {}
+                Ok(site) => match format.serialize(&site) {
+                    Ok(output) => println!("{}", output),
+                    Err(e) => println!("error: {}", e),
+                },
                 Err(e) => {
                     println!("{}", e);
                 }
             };
         }
+        Some(Commands::Load { file, format }) => {
+            let Some(format) = format.or_else(|| DataFormat::from_extension(&file)) else {
+                println!("error: unknown format for '{}', pass --format", file);
+                return;
+            };
+
+            match format.deserialize(&fs::read_to_string(&file).expect("Err")) {
+                // parsing the output again checks the loaded tree like any other site
+                Ok(site) => match parse_str(&file, &site.to_dl()) {
+                    Ok(site) => print!("{}", site.to_dl()),
+                    Err(e) => println!("{}", e),
+                },
+                Err(e) => println!("error: {}", e),
+            };
+        }
         _ => {
-            println!("unkown");
+            println!("unknown");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SITE: &str = r##"
+        page {
+            name: "Home"
+            path: "/"
+            contents: "# Welcome"
+
+            page { name: "Install" path: "/install" contents: "Run the installer." }
+        }
+    "##;
+
+    #[test]
+    fn dumps_load_back_into_the_same_site() {
+        let site = parse_str("test.dl", SITE).unwrap();
+
+        for format in [DataFormat::Json, DataFormat::Yaml, DataFormat::Toml] {
+            let dumped = format.serialize(&site).unwrap();
+            let loaded = format.deserialize(&dumped).unwrap();
+
+            assert_eq!(loaded.to_dl(), site.to_dl());
         }
     }
+
+    #[test]
+    fn formats_follow_the_file_extension() {
+        assert!(matches!(
+            DataFormat::from_extension("site.yml"),
+            Some(DataFormat::Yaml)
+        ));
+        assert!(matches!(
+            DataFormat::from_extension("site.toml"),
+            Some(DataFormat::Toml)
+        ));
+        assert!(DataFormat::from_extension("site.dl").is_none());
+    }
+
+    #[test]
+    fn var_flags_take_precedence_over_the_environment() {
+        let environment = [
+            ("DL_VAR_version", "env"),
+            ("DL_VAR_channel", "env"),
+            ("HOME", "/root"),
+        ]
+        .map(|(key, value)| (key.to_string(), value.to_string()));
+        let options = parse_options(
+            environment.into_iter(),
+            vec![("version".to_string(), "flag".to_string())],
+        );
+        let site = parse_str_with(
+            "test.dl",
+            r#"
+            vars { version: "file" channel: "file" product: "file" }
+            page { name: "${version} ${channel} ${product}" path: "/" contents: "x" }
+            "#,
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(site.pages()[0].name(), "flag env file");
+        assert!(!options.variables().contains_key("HOME"));
+    }
+
+    #[test]
+    fn variables_are_split_at_the_first_equals_sign() {
+        assert_eq!(
+            parse_variable("query=a=b"),
+            Ok(("query".to_string(), "a=b".to_string()))
+        );
+        assert!(parse_variable("=value").is_err());
+    }
 }
@@ -0,0 +1,122 @@
+/// A condition on the active build profiles, written as profile names joined with `&&`
+/// and `||`, each of which can be negated with `!`, e.g. `internal && !legacy || beta`.
+/// `&&` binds tighter than `||`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    /// Alternatives joined with `||`, each a list of `(negated, name)` joined with `&&`.
+    pub alternatives: Vec<Vec<(bool, String)>>,
+}
+
+impl Condition {
+    /// Parses a condition, or returns the message and help of what's wrong with it.
+    pub fn parse(text: &str) -> Result<Self, (String, String)> {
+        let mut alternatives = Vec::new();
+
+        for alternative in text.split("||") {
+            let mut terms = Vec::new();
+
+            for term in alternative.split("&&") {
+                let term = term.trim();
+                let (negated, name) = match term.strip_prefix('!') {
+                    Some(name) => (true, name.trim()),
+                    None => (false, term),
+                };
+
+                if !is_profile_name(name) {
+                    let message = if name.is_empty() {
+                        format!("missing profile name in condition '{}'", text.trim())
+                    } else {
+                        format!(
+                            "invalid profile name '{name}' in condition '{}'",
+                            text.trim()
+                        )
+                    };
+
+                    return Err((
+                        message,
+                        "conditions are profile names joined with && and ||, e.g. 'internal && !legacy'"
+                            .to_string(),
+                    ));
+                }
+
+                terms.push((negated, name.to_string()));
+            }
+
+            alternatives.push(terms);
+        }
+
+        Ok(Self { alternatives })
+    }
+
+    /// Whether the condition holds when exactly `profiles` are active.
+    pub fn matches(&self, profiles: &[String]) -> bool {
+        self.alternatives.iter().any(|terms| {
+            terms
+                .iter()
+                .all(|(negated, name)| profiles.contains(name) != *negated)
+        })
+    }
+}
+
+fn is_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Condition;
+
+    fn matches(condition: &str, profiles: &[&str]) -> bool {
+        let profiles = profiles
+            .iter()
+            .map(|profile| profile.to_string())
+            .collect::<Vec<_>>();
+
+        Condition::parse(condition).unwrap().matches(&profiles)
+    }
+
+    #[test]
+    fn conditions_are_parsed_into_alternatives() {
+        let condition = Condition::parse("internal && !legacy || beta").unwrap();
+
+        assert_eq!(
+            condition.alternatives,
+            [
+                vec![
+                    (false, "internal".to_string()),
+                    (true, "legacy".to_string())
+                ],
+                vec![(false, "beta".to_string())],
+            ]
+        );
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert!(matches("internal && !legacy || beta", &["internal"]));
+        assert!(matches("internal && !legacy || beta", &["beta", "legacy"]));
+        assert!(!matches(
+            "internal && !legacy || beta",
+            &["internal", "legacy"]
+        ));
+        assert!(!matches("internal && !legacy || beta", &[]));
+        assert!(matches("! legacy", &[]));
+    }
+
+    #[test]
+    fn malformed_conditions_are_errors() {
+        assert_eq!(
+            Condition::parse("internal &&").unwrap_err().0,
+            "missing profile name in condition 'internal &&'"
+        );
+        assert_eq!(
+            Condition::parse("internal & beta").unwrap_err().0,
+            "invalid profile name 'internal & beta' in condition 'internal & beta'"
+        );
+        assert!(Condition::parse("").is_err());
+        assert!(Condition::parse("a || || b").is_err());
+    }
+}
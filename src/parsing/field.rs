@@ -0,0 +1,49 @@
+use crate::{
+    errors::standard_error::StandardError, lexing::position::Position, parsing::value::Value,
+};
+
+/// A parsed `name: value` pair, remembering where the value was written so that a value of
+/// the wrong kind can be reported.
+#[derive(Debug, Clone)]
+pub struct Field {
+    pub name: String,
+    pub name_pos: (Position, Position),
+    pub value: Value,
+    pub pos_start: Position,
+    pub pos_end: Position,
+}
+
+impl Field {
+    pub fn string(&self) -> Result<String, StandardError> {
+        match &self.value {
+            Value::Str(string) => Ok(string.clone()),
+            other => Err(self.wrong_kind(other, "string")),
+        }
+    }
+
+    pub fn number(&self) -> Result<i64, StandardError> {
+        match &self.value {
+            Value::Number(number) => Ok(*number),
+            other => Err(self.wrong_kind(other, "number")),
+        }
+    }
+
+    pub fn boolean(&self) -> Result<bool, StandardError> {
+        match &self.value {
+            Value::Bool(boolean) => Ok(*boolean),
+            other => Err(self.wrong_kind(other, "boolean")),
+        }
+    }
+
+    fn wrong_kind(&self, value: &Value, expected: &str) -> StandardError {
+        StandardError::new(
+            &format!("expected {expected} value, found {}", value.kind()),
+            self.pos_start.clone(),
+            self.pos_end.clone(),
+            Some(&format!(
+                "the value of '{}' must be a {expected}",
+                self.name
+            )),
+        )
+    }
+}
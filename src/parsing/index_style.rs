@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// The names `index` and `{{#children}}` take, in the order they're listed in errors.
+pub const INDEX_STYLES: [&str; 3] = ["list", "cards", "auto"];
+
+/// How a listing of the subpages of a page is laid out, set with `index` on the page or
+/// passed to `{{#children}}`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndexStyle {
+    /// A list of titles, each followed by its description.
+    #[default]
+    List,
+    /// A grid of cards with the title and description of each page.
+    Cards,
+}
+
+impl IndexStyle {
+    /// `auto` picks the default style.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "list" | "auto" => Some(Self::List),
+            "cards" => Some(Self::Cards),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::List => "list",
+            Self::Cards => "cards",
+        }
+    }
+}
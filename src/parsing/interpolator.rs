@@ -0,0 +1,452 @@
+use crate::{
+    errors::standard_error::{StandardError, closest_match},
+    lexing::position::Position,
+    parsing::{nav_entry::NavEntry, page::Page, site::Site},
+};
+use std::collections::BTreeMap;
+
+/// Replaces `${name}` in the strings of a site with the value of the variable. It runs once
+/// the whole file is parsed, so variables can be used before they are declared. `$${` is
+/// written as a literal `${`.
+pub struct Interpolator {
+    /// The values of the variables as written. They're resolved every time they're used,
+    /// never stored resolved, so that a `$${` in them is only unescaped once.
+    pub values: BTreeMap<String, String>,
+}
+
+impl Interpolator {
+    /// Checks that the variables of the site, which can use each other, resolve, with
+    /// `overrides` replacing or adding to the ones declared in the file.
+    pub fn new(site: &Site, overrides: &BTreeMap<String, String>) -> Result<Self, StandardError> {
+        let mut interpolator = Self {
+            values: site
+                .variables
+                .iter()
+                .map(|variable| (variable.name.clone(), variable.value.clone()))
+                .collect(),
+        };
+
+        interpolator.values.extend(overrides.clone());
+
+        // resolved up front so that problems are reported at the variable that has them
+        for variable in &site.variables {
+            if !overrides.contains_key(&variable.name) {
+                let anchor = variable.name_pos.as_ref().map(|(pos_start, _)| pos_start);
+
+                interpolator.resolve_variable(&variable.name, anchor)?;
+            }
+        }
+
+        for name in overrides.keys() {
+            interpolator.resolve_variable(name, None)?;
+        }
+
+        Ok(interpolator)
+    }
+
+    /// The value of the variable called `name`, with the variables it uses resolved.
+    fn resolve_variable(
+        &self,
+        name: &str,
+        anchor: Option<&Position>,
+    ) -> Result<String, StandardError> {
+        let (name, value) = self
+            .values
+            .get_key_value(name)
+            .expect("Error resolving a variable that isn't declared");
+
+        self.resolve(value, anchor, &mut vec![name.as_str()])
+    }
+
+    pub fn interpolate(&self, site: &mut Site) -> Result<(), StandardError> {
+        let anchor = site.config.pos_start.clone();
+
+        if let Some(templates) = &site.config.templates {
+            site.config.templates = Some(self.interpolate_str(templates, anchor.as_ref())?);
+        }
+
+        for value in [
+            &mut site.config.url,
+            &mut site.config.description,
+            &mut site.config.author,
+            &mut site.config.image,
+            &mut site.config.robots_txt,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            *value = self.interpolate_str(value, anchor.as_ref())?;
+        }
+
+        for keyword in &mut site.config.keywords {
+            *keyword = self.interpolate_str(keyword, anchor.as_ref())?;
+        }
+
+        for color in site.config.palette.values_mut() {
+            *color = self.interpolate_str(color, anchor.as_ref())?;
+        }
+
+        for snippet in &mut site.snippets {
+            snippet.markdown_contents =
+                self.interpolate_str(&snippet.markdown_contents, snippet.pos_start.as_ref())?;
+        }
+
+        for variable in &mut site.variables {
+            let anchor = variable.name_pos.as_ref().map(|(pos_start, _)| pos_start);
+
+            variable.value = self.resolve_variable(&variable.name, anchor)?;
+        }
+
+        self.interpolate_pages(&mut site.pages)?;
+
+        if let Some(nav) = &mut site.nav {
+            self.interpolate_nav(nav)?;
+        }
+
+        Ok(())
+    }
+
+    fn interpolate_pages(&self, pages: &mut [Page]) -> Result<(), StandardError> {
+        for page in pages {
+            let anchor = page.pos_start.clone();
+            let interpolate = |text: &str| self.interpolate_str(text, anchor.as_ref());
+
+            page.name = interpolate(&page.name)?;
+            page.path = interpolate(&page.path)?;
+            page.markdown_contents = interpolate(&page.markdown_contents)?;
+
+            for value in [
+                &mut page.layout,
+                &mut page.date,
+                &mut page.description,
+                &mut page.author,
+                &mut page.image,
+                &mut page.canonical,
+                &mut page.condition,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                *value = interpolate(value)?;
+            }
+
+            for value in page.keywords.iter_mut().chain(&mut page.redirect_from) {
+                *value = interpolate(value)?;
+            }
+
+            self.interpolate_pages(&mut page.subpages)?;
+        }
+
+        Ok(())
+    }
+
+    /// Interpolates the entries of the navigation. Entries of pages declared between the
+    /// other pages copy the path of the page as written, so it's interpolated here too.
+    fn interpolate_nav(&self, entries: &mut [NavEntry]) -> Result<(), StandardError> {
+        for entry in entries {
+            match entry {
+                NavEntry::Page {
+                    path,
+                    name,
+                    path_pos,
+                } => {
+                    let anchor = path_pos.as_ref().map(|(pos_start, _)| pos_start);
+
+                    *path = self.interpolate_str(path, anchor)?;
+
+                    if let Some(name) = name {
+                        *name = self.interpolate_str(name, anchor)?;
+                    }
+                }
+                NavEntry::Section {
+                    name,
+                    entries,
+                    pos_start,
+                } => {
+                    *name = self.interpolate_str(name, pos_start.as_ref())?;
+                    self.interpolate_nav(entries)?;
+                }
+                NavEntry::Link(link) => {
+                    link.name = self.interpolate_str(&link.name, link.pos_start.as_ref())?;
+                    link.url = self.interpolate_str(&link.url, link.pos_start.as_ref())?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// `anchor` is where the block holding `text` starts, to find the reference in the
+    /// source when reporting an undefined variable.
+    fn interpolate_str(
+        &self,
+        text: &str,
+        anchor: Option<&Position>,
+    ) -> Result<String, StandardError> {
+        self.resolve(text, anchor, &mut Vec::new())
+    }
+
+    /// `stack` holds the variables being resolved, to catch variables defined through
+    /// themselves.
+    fn resolve<'a>(
+        &'a self,
+        text: &str,
+        anchor: Option<&Position>,
+        stack: &mut Vec<&'a str>,
+    ) -> Result<String, StandardError> {
+        let mut output = String::new();
+        let mut rest = text;
+
+        while let Some(start) = rest.find("${") {
+            if rest[..start].ends_with('$') {
+                output.push_str(&rest[..start - 1]);
+                output.push_str("${");
+                rest = &rest[start + 2..];
+                continue;
+            }
+
+            let Some(length) = rest[start..].find('}') else {
+                break;
+            };
+            let reference = &rest[start..start + length + 1];
+            let name = reference[2..reference.len() - 1].trim();
+
+            output.push_str(&rest[..start]);
+            rest = &rest[start + reference.len()..];
+
+            let Some((name, value)) = self.values.get_key_value(name) else {
+                return Err(self.undefined(name, reference, anchor));
+            };
+
+            if stack.contains(&name.as_str()) {
+                let (pos_start, pos_end) = range_of(reference, anchor);
+
+                return Err(StandardError::new(
+                    &format!("variable '{name}' is defined through itself"),
+                    pos_start,
+                    pos_end,
+                    Some(&format!("through '{}' -> '{name}'", stack.join("' -> '"))),
+                ));
+            }
+
+            stack.push(name);
+            output.push_str(&self.resolve(value, anchor, stack)?);
+            stack.pop();
+        }
+
+        output.push_str(rest);
+
+        Ok(output)
+    }
+
+    fn undefined(&self, name: &str, reference: &str, anchor: Option<&Position>) -> StandardError {
+        let (pos_start, pos_end) = range_of(reference, anchor);
+        let names = self.values.keys().map(String::as_str).collect::<Vec<_>>();
+        let help = match closest_match(name, &names) {
+            Some(suggestion) => format!("did you mean '{suggestion}'?"),
+            None => {
+                format!("declare it with let {name} = \"...\", or pass it with --var {name}=...")
+            }
+        };
+
+        StandardError::new(
+            &format!("undefined variable '{name}'"),
+            pos_start,
+            pos_end,
+            Some(&help),
+        )
+    }
+}
+
+/// Where `reference` is written after `anchor`, or `anchor` itself when it can't be found.
+fn range_of(reference: &str, anchor: Option<&Position>) -> (Position, Position) {
+    let anchor = anchor
+        .cloned()
+        .unwrap_or_else(|| Position::new(0, 0, 0, "<unknown>", ""));
+
+    anchor.find_after(reference).unwrap_or_else(|| {
+        let mut pos_end = anchor.clone();
+        pos_end.advance(None);
+
+        (anchor, pos_end)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ParseOptions, parse_str, parse_str_with, parsing::nav_entry::NavEntry};
+
+    #[test]
+    fn escaped_references_are_unescaped_once() {
+        let site = parse_str(
+            "test.dl",
+            r#"
+            let lit = "$${HOME}"
+            let both = "${lit} and $${x}"
+            page { name: "${lit}" path: "/" contents: "${both}" }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(site.pages[0].name, "${HOME}");
+        assert_eq!(site.pages[0].markdown_contents, "${HOME} and ${x}");
+        assert_eq!(site.variables[0].value, "${HOME}");
+    }
+
+    #[test]
+    fn variables_can_use_each_other_in_any_order() {
+        let site = parse_str(
+            "test.dl",
+            r#"
+            page { name: "${title}" path: "/" contents: "x" }
+            let title = "${product} ${version}"
+            vars { product: "DocsLegend" version: "2.3.1" }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(site.pages[0].name, "DocsLegend 2.3.1");
+    }
+
+    #[test]
+    fn cycles_are_errors() {
+        let error = parse_str(
+            "test.dl",
+            r#"
+            let a = "${b}"
+            let b = "${a}"
+            page { name: "${a}" path: "/" contents: "x" }
+            "#,
+        )
+        .unwrap_err();
+
+        assert_eq!(error.text, "variable 'a' is defined through itself");
+    }
+
+    #[test]
+    fn undefined_variables_suggest_the_closest_name() {
+        let error = parse_str(
+            "test.dl",
+            r#"
+            let version = "1"
+            page { name: "${verison}" path: "/" contents: "x" }
+            "#,
+        )
+        .unwrap_err();
+
+        assert_eq!(error.text, "undefined variable 'verison'");
+        assert_eq!(error.help.as_deref(), Some("did you mean 'version'?"));
+    }
+
+    #[test]
+    fn overrides_replace_declared_values() {
+        let source = r#"
+            let version = "1"
+            let title = "v${version}"
+            page { name: "${title}" path: "/" contents: "${extra}" }
+            "#;
+        let options = ParseOptions::new()
+            .with_variable("version", "2")
+            .with_variable("extra", "added");
+        let site = parse_str_with("test.dl", source, &options).unwrap();
+
+        assert_eq!(site.pages[0].name, "v2");
+        assert_eq!(site.pages[0].markdown_contents, "added");
+    }
+
+    #[test]
+    fn dates_and_conditions_are_interpolated() {
+        let source = r#"
+            vars { released: "2024-05-01" audience: "internal" }
+            page {
+                name: "Home"
+                path: "/"
+                contents: "x"
+                date: "${released}"
+                when: "${audience} || beta"
+            }
+            "#;
+        let site = parse_str("test.dl", source).unwrap();
+
+        assert_eq!(site.pages[0].date(), Some("2024-05-01"));
+        assert_eq!(site.pages[0].condition.as_deref(), Some("internal || beta"));
+
+        let options = ParseOptions::new().with_variable("released", "May 1st");
+        let error = parse_str_with("test.dl", source, &options).unwrap_err();
+
+        assert_eq!(error.text, "invalid date 'May 1st' for page 'Home'");
+    }
+
+    #[test]
+    fn navigation_entries_are_interpolated() {
+        let site = parse_str(
+            "test.dl",
+            r#"
+            vars { repo: "https://github.com/docslegend/dl" guides: "/guides" }
+            nav {
+                page "${guides}" { name: "All ${product}" }
+                section "${product}" { link { name: "${product} on GitHub" url: "${repo}" } }
+            }
+            let product = "DocsLegend"
+            page { name: "Guides" path: "${guides}" contents: "x" }
+            "#,
+        )
+        .unwrap();
+
+        let [
+            NavEntry::Page { path, name, .. },
+            NavEntry::Section {
+                name: section,
+                entries,
+                ..
+            },
+        ] = site.nav().unwrap()
+        else {
+            panic!("unexpected nav entries");
+        };
+        let [NavEntry::Link(link)] = entries.as_slice() else {
+            panic!("unexpected section entries");
+        };
+
+        assert_eq!(path, "/guides");
+        assert_eq!(name.as_deref(), Some("All DocsLegend"));
+        assert_eq!(section, "DocsLegend");
+        assert_eq!(link.name, "DocsLegend on GitHub");
+        assert_eq!(link.url, "https://github.com/docslegend/dl");
+    }
+
+    #[test]
+    fn declared_entries_follow_their_pages() {
+        let site = parse_str(
+            "test.dl",
+            r#"
+            let base = "/docs"
+            section "Guides" { page { name: "Install" path: "${base}/install" contents: "x" } }
+            "#,
+        )
+        .unwrap();
+        let [NavEntry::Section { entries, .. }] = site.nav().unwrap() else {
+            panic!("unexpected nav entries");
+        };
+
+        assert!(matches!(
+            entries.as_slice(),
+            [NavEntry::Page { path, .. }] if path == "/docs/install"
+        ));
+    }
+
+    #[test]
+    fn undefined_variables_in_the_nav_point_at_their_entry() {
+        let error = parse_str(
+            "test.dl",
+            r#"
+            nav { link { name: "Home" url: "${homepage}" } }
+            page { name: "Home" path: "/" contents: "x" }
+            "#,
+        )
+        .unwrap_err();
+
+        assert_eq!(error.text, "undefined variable 'homepage'");
+        assert_eq!(error.pos_start.line_num, 1);
+    }
+}
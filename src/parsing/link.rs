@@ -0,0 +1,35 @@
+use crate::lexing::position::Position;
+use serde::{Deserialize, Serialize};
+
+/// A `link { name, url }` entry of the navigation, which points anywhere, such as the
+/// repository of the project, and produces no page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Link {
+    pub(crate) name: String,
+    pub(crate) url: String,
+    #[serde(skip)]
+    pub(crate) pos_start: Option<Position>,
+}
+
+impl Link {
+    pub fn new(name: &str, url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            url: url.to_string(),
+            pos_start: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Whether the link leaves the site, which the navigation marks.
+    pub fn is_external(&self) -> bool {
+        self.url.starts_with("//") || self.url.contains("://") || self.url.starts_with("mailto:")
+    }
+}
@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+/// The names of the Markdown extensions, as written in the `markdown { }` block of a site.
+pub const MARKDOWN_EXTENSIONS: [&str; 9] = [
+    "tables",
+    "footnotes",
+    "strikethrough",
+    "tasklists",
+    "heading_attributes",
+    "definition_lists",
+    "smart_punctuation",
+    "containers",
+    "math",
+];
+
+/// Which Markdown extensions pages are rendered with. Every extension but math is on unless
+/// it's turned off in the `markdown { }` block of the `site` block.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MarkdownExtensions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) tables: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) footnotes: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) strikethrough: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) tasklists: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) heading_attributes: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) definition_lists: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) smart_punctuation: Option<bool>,
+    /// `:::note` fenced admonitions, next to the `> [!NOTE]` blockquotes that always work.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) containers: Option<bool>,
+    /// `$inline$` and `$$display$$` math, off by default so that dollar signs stay text.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) math: Option<bool>,
+}
+
+impl MarkdownExtensions {
+    /// Whether the extension called `name` is on, see [`MARKDOWN_EXTENSIONS`].
+    pub fn enabled(&self, name: &str) -> bool {
+        self.setting(name).unwrap_or(name != "math")
+    }
+
+    /// The extensions set explicitly, in the order of [`MARKDOWN_EXTENSIONS`].
+    pub(crate) fn settings(&self) -> Vec<(&'static str, bool)> {
+        MARKDOWN_EXTENSIONS
+            .iter()
+            .filter_map(|name| Some((*name, self.setting(name)?)))
+            .collect()
+    }
+
+    pub(crate) fn set(&mut self, name: &str, enabled: bool) {
+        let setting = match name {
+            "tables" => &mut self.tables,
+            "footnotes" => &mut self.footnotes,
+            "strikethrough" => &mut self.strikethrough,
+            "tasklists" => &mut self.tasklists,
+            "heading_attributes" => &mut self.heading_attributes,
+            "definition_lists" => &mut self.definition_lists,
+            "smart_punctuation" => &mut self.smart_punctuation,
+            "containers" => &mut self.containers,
+            "math" => &mut self.math,
+            _ => return,
+        };
+
+        *setting = Some(enabled);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.settings().is_empty()
+    }
+
+    fn setting(&self, name: &str) -> Option<bool> {
+        match name {
+            "tables" => self.tables,
+            "footnotes" => self.footnotes,
+            "strikethrough" => self.strikethrough,
+            "tasklists" => self.tasklists,
+            "heading_attributes" => self.heading_attributes,
+            "definition_lists" => self.definition_lists,
+            "smart_punctuation" => self.smart_punctuation,
+            "containers" => self.containers,
+            "math" => self.math,
+            _ => None,
+        }
+    }
+}
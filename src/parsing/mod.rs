@@ -1,3 +1,19 @@
+pub mod condition;
+pub mod field;
+pub mod index_style;
+pub mod interpolator;
+pub mod link;
+pub mod markdown_extensions;
+pub mod nav_entry;
+pub mod node;
 pub mod page;
+pub mod parse_options;
 pub mod parse_result;
 pub mod parser;
+pub mod redirect_outputs;
+pub mod site;
+pub mod site_config;
+pub mod snippet;
+pub mod sort_order;
+pub mod value;
+pub mod variable;
@@ -0,0 +1,50 @@
+use crate::{lexing::position::Position, parsing::link::Link};
+use serde::{Deserialize, Serialize};
+
+/// One entry of a `nav { }` block, which lays out the sidebar apart from where pages are
+/// declared. Links and sections declared outside of one make up the entries of the
+/// navigation in the order they're declared.
+///
+/// ```text
+/// nav {
+///     page "/install" { name: "Installation" }
+///     section "Guides" {
+///         page "/guides/deploy"
+///     }
+///     link { name: "GitHub", url: "https://github.com/docslegend/dl" }
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum NavEntry {
+    /// The page at `path`, under `name` when set, with its subpages below it.
+    Page {
+        path: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        /// Where the path is written, to point at paths no page has.
+        #[serde(skip)]
+        path_pos: Option<(Position, Position)>,
+    },
+    /// A heading grouping the entries under it, which isn't a page itself.
+    Section {
+        name: String,
+        #[serde(default)]
+        entries: Vec<NavEntry>,
+        #[serde(skip)]
+        pos_start: Option<Position>,
+    },
+    Link(Link),
+}
+
+impl NavEntry {
+    /// This entry followed by all of the entries under it, depth first.
+    pub fn iter(&self) -> Box<dyn Iterator<Item = &NavEntry> + '_> {
+        match self {
+            Self::Page { .. } | Self::Link(_) => Box::new(std::iter::once(self)),
+            Self::Section { entries, .. } => {
+                Box::new(std::iter::once(self).chain(entries.iter().flat_map(|entry| entry.iter())))
+            }
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use crate::{
+    lexing::position::Position,
+    parsing::{
+        link::Link, nav_entry::NavEntry, page::Page, site_config::SiteConfig, snippet::Snippet,
+        variable::Variable,
+    },
+};
+
+/// Anything the parser can produce from a block of `.dl` source.
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum Node {
+    Page(Page),
+    Site(SiteConfig),
+    Snippet(Snippet),
+    Variables(Vec<Variable>),
+    /// The entries of a `nav { }` block and where it starts.
+    Nav(Vec<NavEntry>, Position),
+    Link(Link),
+    /// A `section "Name" { }` of pages and links outside of a `nav` block, and where it
+    /// starts.
+    Section(String, Vec<Node>, Position),
+    Statements(Vec<Node>),
+}
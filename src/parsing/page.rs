@@ -1,9 +1,87 @@
-#[derive(Debug, Clone)]
+use crate::{
+    lexing::position::Position,
+    parsing::{index_style::IndexStyle, sort_order::SortOrder},
+};
+use serde::{Deserialize, Serialize};
+
+/// A single page of the site and the pages nested under it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Page {
-    pub name: String,
-    pub path: String,
-    pub markdown_contents: String,
-    pub subpages: Vec<Page>,
+    pub(crate) name: String,
+    pub(crate) path: String,
+    #[serde(rename = "contents")]
+    pub(crate) markdown_contents: String,
+    /// The name of the layout template the page is rendered with, `default` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) layout: Option<String>,
+    /// Whether the page shows an "On this page" table of contents.
+    #[serde(default = "default_toc", skip_serializing_if = "is_default_toc")]
+    pub(crate) toc: bool,
+    /// Where the page goes among its siblings when they're sorted by weight, lowest first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) order: Option<i64>,
+    /// When the page was written, as `YYYY-MM-DD`, for sorting by date.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) date: Option<String>,
+    /// Where the date is written, to point at it when it isn't a date.
+    #[serde(skip)]
+    pub(crate) date_range: Option<(Position, Position)>,
+    /// How the subpages are ordered, by weight when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) sort: Option<SortOrder>,
+    /// How a listing of the subpages is laid out after the contents, when the page has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) index: Option<IndexStyle>,
+    /// A summary of the page for search engines and link previews, the one of the site when
+    /// unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) description: Option<String>,
+    /// Where the description is written, to point at it when it's too long.
+    #[serde(skip)]
+    pub(crate) description_range: Option<(Position, Position)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) keywords: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) author: Option<String>,
+    /// The image of link previews, as a URL or a path of the site.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) image: Option<String>,
+    /// Where search engines should consider the page to be, as a URL or a path of the site,
+    /// the path of the page when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) canonical: Option<String>,
+    /// Old paths of the page, which redirect to it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) redirect_from: Vec<String>,
+    /// Where the old paths are written, to point at the ones that collide.
+    #[serde(skip)]
+    pub(crate) redirect_from_range: Option<(Position, Position)>,
+    /// Drafts are only built when asked for, to preview them.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub(crate) draft: bool,
+    /// Hidden pages are built, but left out of the navigation, previous/next links and
+    /// search.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub(crate) hidden: bool,
+    /// Unlisted pages are built and navigable, but left out of sitemaps.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub(crate) unlisted: bool,
+    /// Which build profiles the page is part of, such as `internal` or `!internal`. Pages
+    /// without a condition are always built.
+    #[serde(rename = "when", default, skip_serializing_if = "Option::is_none")]
+    pub(crate) condition: Option<String>,
+    /// Where the condition is written, to point at mistakes in it.
+    #[serde(skip)]
+    pub(crate) condition_range: Option<(Position, Position)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) pos_start: Option<Position>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) pos_end: Option<Position>,
+    /// Where the string of the `contents` field opens, to point at problems inside it.
+    #[serde(skip)]
+    pub(crate) contents_pos: Option<Position>,
+    #[serde(default)]
+    pub(crate) subpages: Vec<Page>,
 }
 
 impl Page {
@@ -12,7 +90,318 @@ impl Page {
             name: name.to_string(),
             path: path.to_string(),
             markdown_contents: markdown_contents.to_string(),
+            layout: None,
+            toc: true,
+            order: None,
+            date: None,
+            date_range: None,
+            sort: None,
+            index: None,
+            description: None,
+            description_range: None,
+            keywords: Vec::new(),
+            author: None,
+            image: None,
+            canonical: None,
+            redirect_from: Vec::new(),
+            redirect_from_range: None,
+            draft: false,
+            hidden: false,
+            unlisted: false,
+            condition: None,
+            condition_range: None,
+            pos_start: None,
+            pos_end: None,
+            contents_pos: None,
             subpages,
         }
     }
+
+    /// Appends a subpage, for building nested trees in Rust code.
+    pub fn with_subpage(mut self, page: Page) -> Self {
+        self.subpages.push(page);
+
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The raw Markdown contents, exactly as written in the source.
+    pub fn contents(&self) -> &str {
+        &self.markdown_contents
+    }
+
+    pub fn layout(&self) -> Option<&str> {
+        self.layout.as_deref()
+    }
+
+    /// Sets the layout template the page is rendered with.
+    pub fn with_layout(mut self, layout: &str) -> Self {
+        self.layout = Some(layout.to_string());
+
+        self
+    }
+
+    pub fn toc(&self) -> bool {
+        self.toc
+    }
+
+    /// Turns the table of contents of the page on or off.
+    pub fn with_toc(mut self, toc: bool) -> Self {
+        self.toc = toc;
+
+        self
+    }
+
+    pub fn order(&self) -> Option<i64> {
+        self.order
+    }
+
+    /// Sets where the page goes among its siblings when they're sorted by weight.
+    pub fn with_order(mut self, order: i64) -> Self {
+        self.order = Some(order);
+
+        self
+    }
+
+    pub fn date(&self) -> Option<&str> {
+        self.date.as_deref()
+    }
+
+    /// Sets the date of the page, written as `YYYY-MM-DD`.
+    pub fn with_date(mut self, date: &str) -> Self {
+        self.date = Some(date.to_string());
+
+        self
+    }
+
+    pub fn sort(&self) -> SortOrder {
+        self.sort.unwrap_or_default()
+    }
+
+    /// Sets how the subpages are ordered.
+    pub fn with_sort(mut self, sort: SortOrder) -> Self {
+        self.sort = Some(sort);
+
+        self
+    }
+
+    pub fn index(&self) -> Option<IndexStyle> {
+        self.index
+    }
+
+    /// Lists the subpages after the contents of the page, laid out in `style`.
+    pub fn with_index(mut self, style: IndexStyle) -> Self {
+        self.index = Some(style);
+
+        self
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Sets the summary of the page shown by search engines and link previews.
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+
+        self
+    }
+
+    pub fn keywords(&self) -> &[String] {
+        &self.keywords
+    }
+
+    pub fn with_keywords(mut self, keywords: &[&str]) -> Self {
+        self.keywords = keywords.iter().map(|keyword| keyword.to_string()).collect();
+
+        self
+    }
+
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    pub fn with_author(mut self, author: &str) -> Self {
+        self.author = Some(author.to_string());
+
+        self
+    }
+
+    pub fn image(&self) -> Option<&str> {
+        self.image.as_deref()
+    }
+
+    /// Sets the image of link previews, as a URL or a path of the site.
+    pub fn with_image(mut self, image: &str) -> Self {
+        self.image = Some(image.to_string());
+
+        self
+    }
+
+    pub fn canonical(&self) -> Option<&str> {
+        self.canonical.as_deref()
+    }
+
+    /// Sets where search engines should consider the page to be, as a URL or a path of the
+    /// site.
+    pub fn with_canonical(mut self, canonical: &str) -> Self {
+        self.canonical = Some(canonical.to_string());
+
+        self
+    }
+
+    pub fn redirect_from(&self) -> &[String] {
+        &self.redirect_from
+    }
+
+    /// Sets the old paths of the page, which redirect to it.
+    pub fn with_redirect_from(mut self, paths: &[&str]) -> Self {
+        self.redirect_from = paths.iter().map(|path| path.to_string()).collect();
+
+        self
+    }
+
+    /// The range of the `redirect_from` field, or of the `page` keyword when there's no
+    /// position for it.
+    pub(crate) fn redirect_from_range(&self) -> (Position, Position) {
+        self.redirect_from_range
+            .clone()
+            .unwrap_or_else(|| self.keyword_range())
+    }
+
+    pub fn draft(&self) -> bool {
+        self.draft
+    }
+
+    /// Marks the page as a draft, which is only built when drafts are asked for.
+    pub fn with_draft(mut self, draft: bool) -> Self {
+        self.draft = draft;
+
+        self
+    }
+
+    pub fn hidden(&self) -> bool {
+        self.hidden
+    }
+
+    /// Keeps the page, and its subpages, out of the navigation and search.
+    pub fn with_hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+
+        self
+    }
+
+    pub fn unlisted(&self) -> bool {
+        self.unlisted
+    }
+
+    /// Keeps the page out of sitemaps.
+    pub fn with_unlisted(mut self, unlisted: bool) -> Self {
+        self.unlisted = unlisted;
+
+        self
+    }
+
+    /// The names of the states the page is in, such as `draft`, for marking it on previews.
+    pub fn states(&self) -> Vec<&'static str> {
+        [
+            ("draft", self.draft),
+            ("hidden", self.hidden),
+            ("unlisted", self.unlisted),
+        ]
+        .into_iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(state, _)| state)
+        .collect()
+    }
+
+    pub fn condition(&self) -> Option<&str> {
+        self.condition.as_deref()
+    }
+
+    /// Only builds the page, and its subpages, for the profiles `condition` matches.
+    pub fn with_condition(mut self, condition: &str) -> Self {
+        self.condition = Some(condition.to_string());
+
+        self
+    }
+
+    pub fn subpages(&self) -> &[Page] {
+        &self.subpages
+    }
+
+    /// Where the page block starts in the source, if it was parsed from one.
+    pub fn pos_start(&self) -> Option<&Position> {
+        self.pos_start.as_ref()
+    }
+
+    /// Where the page block ends in the source, if it was parsed from one.
+    pub fn pos_end(&self) -> Option<&Position> {
+        self.pos_end.as_ref()
+    }
+
+    /// This page followed by all of its descendants, depth first.
+    pub fn iter(&self) -> Box<dyn Iterator<Item = &Page> + '_> {
+        Box::new(std::iter::once(self).chain(self.subpages.iter().flat_map(|page| page.iter())))
+    }
+
+    /// The range of the `page` keyword that opened this page, used to point errors at it.
+    pub(crate) fn keyword_range(&self) -> (Position, Position) {
+        let pos_start = self
+            .pos_start
+            .clone()
+            .unwrap_or_else(|| Position::new(0, 0, 0, "<unknown>", ""));
+        let mut pos_end = pos_start.clone();
+
+        for _ in 0.."page".len() {
+            pos_end.advance(None);
+        }
+
+        (pos_start, pos_end)
+    }
+
+    /// The range of the date of the page, or of the `page` keyword when there's no position
+    /// for it.
+    pub(crate) fn date_range(&self) -> (Position, Position) {
+        self.date_range
+            .clone()
+            .unwrap_or_else(|| self.keyword_range())
+    }
+
+    /// The range of the condition of the page, or of the `page` keyword when there's no
+    /// position for it.
+    pub(crate) fn condition_range(&self) -> (Position, Position) {
+        self.condition_range
+            .clone()
+            .unwrap_or_else(|| self.keyword_range())
+    }
+
+    /// The range of `text` on the given line of the contents, counting lines like
+    /// `str::lines` does on the contents. Falls back to the `page` keyword for pages that
+    /// weren't parsed from source.
+    pub(crate) fn contents_range(&self, line: usize, text: &str) -> (Position, Position) {
+        match &self.contents_pos {
+            Some(contents_pos) => contents_pos.range_in_string(line, text),
+            None => self.keyword_range(),
+        }
+    }
+}
+
+fn default_toc() -> bool {
+    true
+}
+
+fn is_default_toc(toc: &bool) -> bool {
+    *toc
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
 }
@@ -0,0 +1,24 @@
+use std::collections::BTreeMap;
+
+/// Settings for turning `.dl` source into a [`Site`](crate::Site).
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    pub(crate) variables: BTreeMap<String, String>,
+}
+
+impl ParseOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a variable, replacing the value the file declares for it, if any.
+    pub fn with_variable(mut self, name: &str, value: &str) -> Self {
+        self.variables.insert(name.to_string(), value.to_string());
+
+        self
+    }
+
+    pub fn variables(&self) -> &BTreeMap<String, String> {
+        &self.variables
+    }
+}
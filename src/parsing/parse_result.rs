@@ -1,22 +1,20 @@
-use crate::{errors::standard_error::StandardError, parsing::page::Page};
+use crate::{errors::standard_error::StandardError, parsing::node::Node};
 
 #[derive(Clone)]
 pub struct ParseResult {
     pub error: Option<StandardError>,
-    pub page: Option<Page>,
+    pub node: Option<Node>,
     pub last_registered_advance_count: usize,
     pub advance_count: usize,
-    pub to_reverse_count: usize,
 }
 
 impl ParseResult {
     pub fn new() -> Self {
         Self {
             error: None,
-            page: None,
+            node: None,
             last_registered_advance_count: 0,
             advance_count: 0,
-            to_reverse_count: 0,
         }
     }
 
@@ -25,7 +23,7 @@ impl ParseResult {
         self.advance_count += 1;
     }
 
-    pub fn register(&mut self, parse_result: ParseResult) -> Option<Page> {
+    pub fn register(&mut self, parse_result: ParseResult) -> Option<Node> {
         self.last_registered_advance_count = parse_result.advance_count;
         self.advance_count += parse_result.advance_count;
 
@@ -33,21 +31,11 @@ impl ParseResult {
             self.error = parse_result.error
         }
 
-        parse_result.page
+        parse_result.node
     }
 
-    pub fn try_register(&mut self, parse_result: ParseResult) -> Option<Page> {
-        if parse_result.error.is_some() {
-            self.to_reverse_count = parse_result.advance_count;
-
-            return None;
-        }
-
-        self.register(parse_result)
-    }
-
-    pub fn success(&mut self, page: Option<Page>) -> ParseResult {
-        self.page = page;
+    pub fn success(&mut self, node: Option<Node>) -> ParseResult {
+        self.node = node;
 
         self.clone()
     }
@@ -1,10 +1,24 @@
 use crate::{
-    errors::standard_error::StandardError,
+    errors::standard_error::{StandardError, list_options},
     lexing::{position::Position, token::Token, token_type::TokenType},
-    parsing::page::Page,
-    parsing::parse_result::ParseResult,
+    parsing::{
+        field::Field,
+        index_style::{INDEX_STYLES, IndexStyle},
+        link::Link,
+        markdown_extensions::MARKDOWN_EXTENSIONS,
+        nav_entry::NavEntry,
+        node::Node,
+        page::Page,
+        parse_result::ParseResult,
+        redirect_outputs::REDIRECT_OUTPUTS,
+        site_config::{PALETTE_COLORS, SiteConfig},
+        snippet::Snippet,
+        sort_order::{SORT_ORDERS, SortOrder},
+        value::Value,
+        variable::Variable,
+    },
 };
-use std::sync::Arc;
+use std::{ops::RangeInclusive, sync::Arc};
 
 pub struct Parser {
     pub tokens: Arc<[Token]>,
@@ -31,13 +45,6 @@ impl Parser {
         self.current_token.clone()
     }
 
-    fn reverse(&mut self, amount: usize) -> Option<Token> {
-        self.token_index -= amount as isize;
-        self.update_current_token();
-
-        self.current_token.clone()
-    }
-
     fn update_current_token(&mut self) {
         if self.token_index >= 0 && self.token_index < self.tokens.len() as isize {
             self.current_token = Some(self.tokens[self.token_index as usize].clone());
@@ -72,25 +79,6 @@ impl Parser {
             .clone()
     }
 
-    fn current_pos_range(&self) -> (Position, Position) {
-        (
-            self.current_token
-                .as_ref()
-                .unwrap()
-                .pos_start
-                .as_ref()
-                .unwrap()
-                .clone(),
-            self.current_token
-                .as_ref()
-                .unwrap()
-                .pos_end
-                .as_ref()
-                .unwrap()
-                .clone(),
-        )
-    }
-
     pub fn parse(&mut self) -> ParseResult {
         let mut parse_result = self.statements();
 
@@ -115,141 +103,1047 @@ impl Parser {
             .current_token_ref()
             .matches(TokenType::TT_KEYWORD, "page")
         {
-            parse_result.register_advancement();
-            self.advance();
+            return self.page_expr();
+        }
 
-            let pos_end = self.current_pos_end();
+        if self
+            .current_token_ref()
+            .matches(TokenType::TT_KEYWORD, "site")
+        {
+            return self.site_expr();
+        }
 
-            if self.current_token_copy().token_type != TokenType::TT_LBRACKET {
-                return parse_result.failure(Some(StandardError::new(
-                    "expected '{'",
-                    pos_start,
-                    pos_end,
-                    Some("add a '{' to define the fields of the page"),
-                )));
+        if self
+            .current_token_ref()
+            .matches(TokenType::TT_KEYWORD, "snippet")
+        {
+            return self.snippet_expr();
+        }
+
+        if self
+            .current_token_ref()
+            .matches(TokenType::TT_KEYWORD, "let")
+        {
+            return self.let_expr();
+        }
+
+        if self
+            .current_token_ref()
+            .matches(TokenType::TT_KEYWORD, "vars")
+        {
+            return self.vars_expr();
+        }
+
+        if self
+            .current_token_ref()
+            .matches(TokenType::TT_KEYWORD, "nav")
+        {
+            return self.nav_expr();
+        }
+
+        if self
+            .current_token_ref()
+            .matches(TokenType::TT_KEYWORD, "link")
+        {
+            let mut parse_result = ParseResult::new();
+
+            return match self.link(&mut parse_result) {
+                Ok(link) => parse_result.success(Some(Node::Link(link))),
+                Err(error) => parse_result.failure(Some(error)),
+            };
+        }
+
+        if self
+            .current_token_ref()
+            .matches(TokenType::TT_KEYWORD, "section")
+        {
+            return self.section_expr();
+        }
+
+        parse_result.failure(Some(StandardError::new(
+            "unknown keyword",
+            pos_start,
+            self.current_pos_end(),
+            None,
+        )))
+    }
+
+    fn page_expr(&mut self) -> ParseResult {
+        let mut parse_result = ParseResult::new();
+        let pos_start = self.current_pos_start();
+
+        parse_result.register_advancement();
+        self.advance();
+
+        let pos_end = self.current_pos_end();
+
+        if self.current_token_copy().token_type != TokenType::TT_LBRACKET {
+            return parse_result.failure(Some(StandardError::new(
+                "expected '{'",
+                pos_start,
+                pos_end,
+                Some("add a '{' to define the fields of the page"),
+            )));
+        }
+
+        parse_result.register_advancement();
+        self.advance();
+
+        let allowed_fields = [
+            "name",
+            "path",
+            "contents",
+            "layout",
+            "toc",
+            "draft",
+            "hidden",
+            "unlisted",
+            "when",
+            "if",
+            "order",
+            "weight",
+            "date",
+            "sort",
+            "index",
+            "description",
+            "keywords",
+            "author",
+            "image",
+            "canonical",
+            "redirect_from",
+        ];
+        let mut name_val: Option<String> = None;
+        let mut path_val: Option<String> = None;
+        let mut contents_val: Option<String> = None;
+        let mut layout_val: Option<String> = None;
+        let mut toc_val = true;
+        let mut draft_val = false;
+        let mut hidden_val = false;
+        let mut unlisted_val = false;
+        let mut order_val: Option<i64> = None;
+        let mut date_val: Option<(String, (Position, Position))> = None;
+        let mut sort_val: Option<SortOrder> = None;
+        let mut index_val: Option<IndexStyle> = None;
+        let mut description_val: Option<(String, (Position, Position))> = None;
+        let mut keywords_val: Vec<String> = Vec::new();
+        let mut author_val: Option<String> = None;
+        let mut image_val: Option<String> = None;
+        let mut canonical_val: Option<String> = None;
+        let mut redirect_from_val: Option<(Vec<String>, (Position, Position))> = None;
+        let mut condition_val: Option<(String, (Position, Position))> = None;
+        let mut contents_pos: Option<Position> = None;
+        let mut subpages: Vec<Page> = Vec::new();
+
+        while self.current_token_ref().token_type != TokenType::TT_RBRACKET {
+            if self
+                .current_token_ref()
+                .matches(TokenType::TT_KEYWORD, "page")
+            {
+                let subpage = parse_result.register(self.page_expr());
+
+                if parse_result.error.is_some() {
+                    return parse_result;
+                }
+
+                if let Some(Node::Page(subpage)) = subpage {
+                    subpages.push(subpage);
+                }
+
+                continue;
             }
 
-            parse_result.register_advancement();
-            self.advance();
+            if self.current_token_ref().token_type == TokenType::TT_EOF {
+                break;
+            }
 
-            let allowed_fields = ["name", "path", "contents"];
-            let mut name_val: Option<String> = None;
-            let mut path_val: Option<String> = None;
-            let mut contents_val: Option<String> = None;
-            let mut subpages: Vec<Page> = Vec::new();
+            let field = match self.field(&mut parse_result, "page", &allowed_fields) {
+                Ok(field) => field,
+                Err(error) => return parse_result.failure(Some(error)),
+            };
+            let field_name = field.name.clone();
 
-            while self.current_token_ref().token_type != TokenType::TT_RBRACKET {
-                let field_token = self.current_token_copy();
+            let applied = match field_name.as_str() {
+                "name" => field.string().map(|value| name_val = Some(value)),
+                "path" => field.string().map(|value| path_val = Some(value)),
+                "contents" => field.string().map(|value| {
+                    contents_val = Some(value);
+                    contents_pos = Some(field.pos_start.clone());
+                }),
+                "layout" => field.string().map(|value| layout_val = Some(value)),
+                "toc" => field.boolean().map(|value| toc_val = value),
+                "draft" => field.boolean().map(|value| draft_val = value),
+                "hidden" => field.boolean().map(|value| hidden_val = value),
+                "unlisted" => field.boolean().map(|value| unlisted_val = value),
+                "order" | "weight" => field.number().map(|value| order_val = Some(value)),
+                "date" => field.string().map(|value| {
+                    date_val = Some((value, (field.pos_start.clone(), field.pos_end.clone())));
+                }),
+                "sort" => sort_order(&field).map(|value| sort_val = Some(value)),
+                "index" => index_style(&field).map(|value| index_val = Some(value)),
+                "description" => field.string().map(|value| {
+                    description_val =
+                        Some((value, (field.pos_start.clone(), field.pos_end.clone())));
+                }),
+                "keywords" => comma_separated(&field).map(|value| keywords_val = value),
+                "redirect_from" => comma_separated(&field).map(|value| {
+                    redirect_from_val =
+                        Some((value, (field.pos_start.clone(), field.pos_end.clone())));
+                }),
+                "author" => field.string().map(|value| author_val = Some(value)),
+                "image" => field.string().map(|value| image_val = Some(value)),
+                "canonical" => field.string().map(|value| canonical_val = Some(value)),
+                "when" | "if" => field.string().map(|value| {
+                    condition_val = Some((value, (field.pos_start.clone(), field.pos_end.clone())));
+                }),
+                _ => Ok(()),
+            };
 
-                if field_token.token_type != TokenType::TT_IDENTIFIER {
-                    return parse_result.failure(Some(StandardError::new(
-                        "expected field 'name', 'path', or 'contents'",
-                        pos_start,
-                        pos_end,
-                        None,
-                    )));
+            if let Err(error) = applied {
+                return parse_result.failure(Some(error));
+            }
+        }
+
+        if self.current_token_ref().token_type != TokenType::TT_RBRACKET {
+            return parse_result.failure(Some(StandardError::new(
+                "expected '}' at end of page definition",
+                self.current_pos_start(),
+                self.current_pos_end(),
+                None,
+            )));
+        }
+
+        let block_end = self.current_pos_end();
+
+        parse_result.register_advancement();
+        self.advance();
+
+        // a page that only lists its subpages can leave out its contents
+        let lists_subpages = index_val.is_some() || !subpages.is_empty();
+
+        if name_val.is_none() || path_val.is_none() || (contents_val.is_none() && !lists_subpages) {
+            return parse_result.failure(Some(StandardError::new(
+                "missing one or more required fields",
+                pos_start,
+                pos_end,
+                Some("add the following required fields 'name', 'path', and 'contents'"),
+            )));
+        }
+
+        if contents_val.is_none() {
+            index_val = Some(index_val.unwrap_or_default());
+        }
+
+        let mut page = Page::new(
+            &name_val.unwrap(),
+            &path_val.unwrap(),
+            &contents_val.unwrap_or_default(),
+            subpages,
+        );
+        page.layout = layout_val;
+        page.toc = toc_val;
+        page.draft = draft_val;
+        page.hidden = hidden_val;
+        page.unlisted = unlisted_val;
+        page.order = order_val;
+        page.date = date_val.as_ref().map(|(date, _)| date.clone());
+        page.date_range = date_val.map(|(_, range)| range);
+        page.sort = sort_val;
+        page.index = index_val;
+        page.description = description_val
+            .as_ref()
+            .map(|(description, _)| description.clone());
+        page.description_range = description_val.map(|(_, range)| range);
+        page.keywords = keywords_val;
+        page.author = author_val;
+        page.image = image_val;
+        page.canonical = canonical_val;
+
+        if let Some((redirect_from, range)) = redirect_from_val {
+            page.redirect_from = redirect_from;
+            page.redirect_from_range = Some(range);
+        }
+        page.condition = condition_val
+            .as_ref()
+            .map(|(condition, _)| condition.clone());
+        page.condition_range = condition_val.map(|(_, range)| range);
+        page.pos_start = Some(pos_start);
+        page.pos_end = Some(block_end);
+        page.contents_pos = contents_pos;
+
+        parse_result.success(Some(Node::Page(page)))
+    }
+
+    fn site_expr(&mut self) -> ParseResult {
+        let mut parse_result = ParseResult::new();
+        let pos_start = self.current_pos_start();
+
+        parse_result.register_advancement();
+        self.advance();
+
+        if self.current_token_copy().token_type != TokenType::TT_LBRACKET {
+            return parse_result.failure(Some(StandardError::new(
+                "expected '{'",
+                pos_start,
+                self.current_pos_end(),
+                Some("add a '{' to define the settings of the site"),
+            )));
+        }
+
+        parse_result.register_advancement();
+        self.advance();
+
+        let allowed_fields = [
+            "templates",
+            "navigation_depth",
+            "toc_min_level",
+            "toc_max_level",
+            "search",
+            "line_numbers",
+            "sort",
+            "url",
+            "description",
+            "keywords",
+            "author",
+            "image",
+            "sitemap",
+            "robots",
+            "not_found",
+            "markdown",
+            "redirects",
+            "palette",
+        ];
+        let mut config = SiteConfig {
+            pos_start: Some(pos_start),
+            ..Default::default()
+        };
+        // the last of the toc levels set, which an inverted range is reported at
+        let mut toc_range = None;
+
+        while !matches!(
+            self.current_token_ref().token_type,
+            TokenType::TT_RBRACKET | TokenType::TT_EOF
+        ) {
+            if self
+                .current_token_ref()
+                .matches(TokenType::TT_IDENTIFIER, "markdown")
+            {
+                let fields =
+                    match self.block_fields(&mut parse_result, "markdown", &MARKDOWN_EXTENSIONS) {
+                        Ok(fields) => fields,
+                        Err(error) => return parse_result.failure(Some(error)),
+                    };
+
+                for field in fields {
+                    match field.boolean() {
+                        Ok(enabled) => config.markdown.set(&field.name, enabled),
+                        Err(error) => return parse_result.failure(Some(error)),
+                    }
                 }
 
-                let field_name = field_token.value.clone().unwrap();
+                continue;
+            }
+
+            if self
+                .current_token_ref()
+                .matches(TokenType::TT_IDENTIFIER, "redirects")
+            {
+                let fields =
+                    match self.block_fields(&mut parse_result, "redirects", &REDIRECT_OUTPUTS) {
+                        Ok(fields) => fields,
+                        Err(error) => return parse_result.failure(Some(error)),
+                    };
 
-                if !allowed_fields.contains(&field_name.as_str()) {
-                    return parse_result.failure(Some(StandardError::new(
-                        "invalid page field",
-                        pos_start,
-                        pos_end,
-                        Some("fields are 'name', 'path', and 'contents'"),
-                    )));
+                for field in fields {
+                    match field.boolean() {
+                        Ok(enabled) => config.redirects.set(&field.name, enabled),
+                        Err(error) => return parse_result.failure(Some(error)),
+                    }
                 }
 
-                parse_result.register_advancement();
-                self.advance();
+                continue;
+            }
+
+            if self
+                .current_token_ref()
+                .matches(TokenType::TT_IDENTIFIER, "palette")
+            {
+                let dark_colors = PALETTE_COLORS.map(|color| format!("{color}_dark"));
+                let palette_fields = PALETTE_COLORS
+                    .iter()
+                    .copied()
+                    .chain(dark_colors.iter().map(String::as_str))
+                    .collect::<Vec<_>>();
+                let fields = match self.block_fields(&mut parse_result, "palette", &palette_fields)
+                {
+                    Ok(fields) => fields,
+                    Err(error) => return parse_result.failure(Some(error)),
+                };
+
+                for field in fields {
+                    match field.string() {
+                        Ok(color) => {
+                            config.palette.insert(field.name.clone(), color);
+                        }
+                        Err(error) => return parse_result.failure(Some(error)),
+                    }
+                }
+
+                continue;
+            }
+
+            let field = match self.field(&mut parse_result, "site", &allowed_fields) {
+                Ok(field) => field,
+                Err(error) => return parse_result.failure(Some(error)),
+            };
+            let field_name = field.name.clone();
+
+            let applied = match field_name.as_str() {
+                "templates" => field.string().map(|value| config.templates = Some(value)),
+                "navigation_depth" => number_in(&field, 1..=i64::MAX)
+                    .map(|value| config.navigation_depth = Some(value as usize)),
+                "toc_min_level" => number_in(&field, 1..=6).map(|value| {
+                    config.toc_min_level = Some(value as u8);
+                    toc_range = Some((field.pos_start.clone(), field.pos_end.clone()));
+                }),
+                "toc_max_level" => number_in(&field, 1..=6).map(|value| {
+                    config.toc_max_level = Some(value as u8);
+                    toc_range = Some((field.pos_start.clone(), field.pos_end.clone()));
+                }),
+                "search" => field.boolean().map(|value| config.search = Some(value)),
+                "line_numbers" => field
+                    .boolean()
+                    .map(|value| config.line_numbers = Some(value)),
+                "sort" => sort_order(&field).map(|value| config.sort = Some(value)),
+                "url" => field.string().map(|value| config.url = Some(value)),
+                "description" => field.string().map(|value| {
+                    config.description = Some(value);
+                    config.description_range =
+                        Some((field.pos_start.clone(), field.pos_end.clone()));
+                }),
+                "keywords" => comma_separated(&field).map(|value| config.keywords = value),
+                "author" => field.string().map(|value| config.author = Some(value)),
+                "image" => field.string().map(|value| config.image = Some(value)),
+                "sitemap" => field.boolean().map(|value| config.sitemap = Some(value)),
+                // either turns robots.txt on or off, or gives its contents
+                "robots" => match &field.value {
+                    Value::Str(contents) => {
+                        config.robots_txt = Some(contents.clone());
+                        Ok(())
+                    }
+                    _ => field.boolean().map(|value| config.robots = Some(value)),
+                },
+                "not_found" => field.boolean().map(|value| config.not_found = Some(value)),
+                _ => Ok(()),
+            };
+
+            if let Err(error) = applied {
+                return parse_result.failure(Some(error));
+            }
+        }
+
+        let (toc_min_level, toc_max_level) = config.toc_levels();
+
+        if toc_min_level > toc_max_level
+            && let Some((pos_start, pos_end)) = toc_range
+        {
+            return parse_result.failure(Some(StandardError::new(
+                &format!("toc_min_level {toc_min_level} is above toc_max_level {toc_max_level}"),
+                pos_start,
+                pos_end,
+                Some("the table of contents would always be empty"),
+            )));
+        }
+
+        if self.current_token_ref().token_type != TokenType::TT_RBRACKET {
+            return parse_result.failure(Some(StandardError::new(
+                "expected '}' at end of site definition",
+                self.current_pos_start(),
+                self.current_pos_end(),
+                None,
+            )));
+        }
+
+        parse_result.register_advancement();
+        self.advance();
+
+        parse_result.success(Some(Node::Site(config)))
+    }
+
+    fn snippet_expr(&mut self) -> ParseResult {
+        let mut parse_result = ParseResult::new();
+        let pos_start = self.current_pos_start();
+
+        parse_result.register_advancement();
+        self.advance();
+
+        let name_token = self.current_token_copy();
+
+        if name_token.token_type != TokenType::TT_STR {
+            return parse_result.failure(Some(StandardError::new(
+                "expected snippet name",
+                pos_start,
+                self.current_pos_end(),
+                Some("name the snippet with a string, e.g. snippet \"install\" { ... }"),
+            )));
+        }
+
+        let name_pos = (self.current_pos_start(), self.current_pos_end());
+
+        parse_result.register_advancement();
+        self.advance();
+
+        if self.current_token_copy().token_type != TokenType::TT_LBRACKET {
+            return parse_result.failure(Some(StandardError::new(
+                "expected '{'",
+                pos_start,
+                self.current_pos_end(),
+                Some("add a '{' to define the fields of the snippet"),
+            )));
+        }
+
+        parse_result.register_advancement();
+        self.advance();
+
+        let allowed_fields = ["contents"];
+        let mut contents: Option<(String, Position)> = None;
+
+        while !matches!(
+            self.current_token_ref().token_type,
+            TokenType::TT_RBRACKET | TokenType::TT_EOF
+        ) {
+            let field = match self.field(&mut parse_result, "snippet", &allowed_fields) {
+                Ok(field) => field,
+                Err(error) => return parse_result.failure(Some(error)),
+            };
+
+            match field.string() {
+                Ok(value) => contents = Some((value, field.pos_start.clone())),
+                Err(error) => return parse_result.failure(Some(error)),
+            }
+        }
+
+        if self.current_token_ref().token_type != TokenType::TT_RBRACKET {
+            return parse_result.failure(Some(StandardError::new(
+                "expected '}' at end of snippet definition",
+                self.current_pos_start(),
+                self.current_pos_end(),
+                None,
+            )));
+        }
+
+        parse_result.register_advancement();
+        self.advance();
+
+        let Some((contents, contents_pos)) = contents else {
+            return parse_result.failure(Some(StandardError::new(
+                "missing snippet contents",
+                name_pos.0,
+                name_pos.1,
+                Some("add a 'contents' field with the Markdown of the snippet"),
+            )));
+        };
+
+        let mut snippet = Snippet::new(&name_token.value.unwrap_or_default(), &contents);
+        snippet.pos_start = Some(pos_start);
+        snippet.name_pos = Some(name_pos);
+        snippet.contents_pos = Some(contents_pos);
+
+        parse_result.success(Some(Node::Snippet(snippet)))
+    }
+
+    fn let_expr(&mut self) -> ParseResult {
+        let mut parse_result = ParseResult::new();
+        let pos_start = self.current_pos_start();
+
+        parse_result.register_advancement();
+        self.advance();
+
+        let name_token = self.current_token_copy();
+
+        if !is_name(&name_token) {
+            return parse_result.failure(Some(StandardError::new(
+                "expected variable name",
+                pos_start,
+                self.current_pos_end(),
+                Some("declare variables as let name = \"value\""),
+            )));
+        }
+
+        let name_pos = (self.current_pos_start(), self.current_pos_end());
+
+        parse_result.register_advancement();
+        self.advance();
+
+        if self.current_token_ref().token_type != TokenType::TT_EQUALS {
+            return parse_result.failure(Some(StandardError::new(
+                "expected '='",
+                self.current_pos_start(),
+                self.current_pos_end(),
+                Some("add a '=' followed by the value of the variable"),
+            )));
+        }
 
-                if self.current_token_ref().token_type != TokenType::TT_COLON {
-                    return parse_result.failure(Some(StandardError::new(
-                        "missing ':'",
+        parse_result.register_advancement();
+        self.advance();
+
+        let value_token = self.current_token_copy();
+
+        if value_token.token_type != TokenType::TT_STR {
+            return parse_result.failure(Some(StandardError::new(
+                "expected string value",
+                self.current_pos_start(),
+                self.current_pos_end(),
+                Some("the value of a variable must be a string"),
+            )));
+        }
+
+        parse_result.register_advancement();
+        self.advance();
+
+        let mut variable = Variable::new(
+            &name_token.value.unwrap_or_default(),
+            &value_token.value.unwrap_or_default(),
+        );
+        variable.name_pos = Some(name_pos);
+
+        parse_result.success(Some(Node::Variables(vec![variable])))
+    }
+
+    fn vars_expr(&mut self) -> ParseResult {
+        let mut parse_result = ParseResult::new();
+        let fields = match self.block_fields(&mut parse_result, "vars", &[]) {
+            Ok(fields) => fields,
+            Err(error) => return parse_result.failure(Some(error)),
+        };
+        let mut variables = Vec::new();
+
+        for field in fields {
+            let value = match field.string() {
+                Ok(value) => value,
+                Err(error) => return parse_result.failure(Some(error)),
+            };
+            let mut variable = Variable::new(&field.name, &value);
+            variable.name_pos = Some(field.name_pos.clone());
+
+            variables.push(variable);
+        }
+
+        parse_result.success(Some(Node::Variables(variables)))
+    }
+
+    fn nav_expr(&mut self) -> ParseResult {
+        let mut parse_result = ParseResult::new();
+        let pos_start = self.current_pos_start();
+
+        parse_result.register_advancement();
+        self.advance();
+
+        if self.current_token_ref().token_type != TokenType::TT_LBRACKET {
+            return parse_result.failure(Some(StandardError::new(
+                "expected '{'",
+                pos_start,
+                self.current_pos_end(),
+                Some("add a '{' to list the entries of the navigation"),
+            )));
+        }
+
+        parse_result.register_advancement();
+        self.advance();
+
+        match self.nav_entries(&mut parse_result) {
+            Ok(entries) => parse_result.success(Some(Node::Nav(entries, pos_start))),
+            Err(error) => parse_result.failure(Some(error)),
+        }
+    }
+
+    /// Parses the entries of a `nav` block or of a section in it, up to and including the
+    /// closing '}'.
+    fn nav_entries(
+        &mut self,
+        parse_result: &mut ParseResult,
+    ) -> Result<Vec<NavEntry>, StandardError> {
+        let mut entries = Vec::new();
+
+        while !matches!(
+            self.current_token_ref().token_type,
+            TokenType::TT_RBRACKET | TokenType::TT_EOF
+        ) {
+            if self
+                .current_token_ref()
+                .matches(TokenType::TT_KEYWORD, "link")
+            {
+                entries.push(NavEntry::Link(self.link(parse_result)?));
+                continue;
+            }
+
+            let is_page = self
+                .current_token_ref()
+                .matches(TokenType::TT_KEYWORD, "page");
+            let is_section = self
+                .current_token_ref()
+                .matches(TokenType::TT_KEYWORD, "section");
+
+            if !is_page && !is_section {
+                return Err(StandardError::new(
+                    "expected navigation entry",
+                    self.current_pos_start(),
+                    self.current_pos_end(),
+                    Some(
+                        "entries are page \"/path\", section \"Name\" { ... } and link { name: \"...\" url: \"...\" }",
+                    ),
+                ));
+            }
+
+            parse_result.register_advancement();
+            self.advance();
+
+            let value_token = self.current_token_copy();
+            let value_pos = (self.current_pos_start(), self.current_pos_end());
+
+            if value_token.token_type != TokenType::TT_STR {
+                let help = if is_page {
+                    "write the path of the page in quotes, e.g. page \"/install\""
+                } else {
+                    "write the name of the section in quotes, e.g. section \"Guides\" { ... }"
+                };
+
+                return Err(StandardError::new(
+                    "expected string",
+                    value_pos.0,
+                    value_pos.1,
+                    Some(help),
+                ));
+            }
+
+            let value = value_token.value.unwrap_or_default();
+
+            parse_result.register_advancement();
+            self.advance();
+
+            if is_section {
+                if self.current_token_ref().token_type != TokenType::TT_LBRACKET {
+                    return Err(StandardError::new(
+                        "expected '{'",
                         self.current_pos_start(),
                         self.current_pos_end(),
-                        Some("add a colon to specify the value of the field"),
-                    )));
+                        Some("add a '{' to list the entries of the section"),
+                    ));
                 }
 
                 parse_result.register_advancement();
                 self.advance();
 
-                if self.current_token_ref().token_type != TokenType::TT_STR {
-                    return parse_result.failure(Some(StandardError::new(
-                        "expected string value",
+                entries.push(NavEntry::Section {
+                    name: value,
+                    entries: self.nav_entries(parse_result)?,
+                    pos_start: Some(value_pos.0),
+                });
+
+                continue;
+            }
+
+            let mut name = None;
+
+            if self.current_token_ref().token_type == TokenType::TT_LBRACKET {
+                parse_result.register_advancement();
+                self.advance();
+
+                while !matches!(
+                    self.current_token_ref().token_type,
+                    TokenType::TT_RBRACKET | TokenType::TT_EOF
+                ) {
+                    name = Some(self.field(parse_result, "nav page", &["name"])?.string()?);
+                }
+
+                if self.current_token_ref().token_type != TokenType::TT_RBRACKET {
+                    return Err(StandardError::new(
+                        "expected '}' at end of nav page",
                         self.current_pos_start(),
                         self.current_pos_end(),
-                        Some("the value of a field must be a string"),
-                    )));
+                        None,
+                    ));
                 }
 
-                let value = self.current_token_copy().value.unwrap();
-
                 parse_result.register_advancement();
                 self.advance();
+            }
 
-                match field_name.as_str() {
-                    "name" => name_val = Some(value),
-                    "path" => path_val = Some(value),
-                    "contents" => contents_val = Some(value),
-                    _ => {}
-                }
+            entries.push(NavEntry::Page {
+                path: value,
+                name,
+                path_pos: Some(value_pos),
+            });
+        }
 
-                if self
-                    .current_token_ref()
-                    .matches(TokenType::TT_KEYWORD, "page")
-                {
-                    let subpage = parse_result.register(self.expr());
+        if self.current_token_ref().token_type != TokenType::TT_RBRACKET {
+            return Err(StandardError::new(
+                "expected '}' at end of nav definition",
+                self.current_pos_start(),
+                self.current_pos_end(),
+                None,
+            ));
+        }
 
-                    if parse_result.error.is_some() {
-                        return parse_result;
-                    }
+        parse_result.register_advancement();
+        self.advance();
 
-                    subpages.push(subpage.unwrap());
+        Ok(entries)
+    }
 
-                    continue;
-                }
-            }
+    /// Parses a `section "Name" { }` of page blocks, links and sections outside of a `nav`
+    /// block.
+    fn section_expr(&mut self) -> ParseResult {
+        let mut parse_result = ParseResult::new();
+        let pos_start = self.current_pos_start();
 
-            if self.current_token_ref().token_type != TokenType::TT_RBRACKET {
+        parse_result.register_advancement();
+        self.advance();
+
+        let name_token = self.current_token_copy();
+
+        if name_token.token_type != TokenType::TT_STR {
+            return parse_result.failure(Some(StandardError::new(
+                "expected section name",
+                pos_start,
+                self.current_pos_end(),
+                Some("write the name of the section in quotes, e.g. section \"Guides\" { ... }"),
+            )));
+        }
+
+        parse_result.register_advancement();
+        self.advance();
+
+        if self.current_token_ref().token_type != TokenType::TT_LBRACKET {
+            return parse_result.failure(Some(StandardError::new(
+                "expected '{'",
+                self.current_pos_start(),
+                self.current_pos_end(),
+                Some("add a '{' to declare the pages of the section"),
+            )));
+        }
+
+        parse_result.register_advancement();
+        self.advance();
+
+        let mut nodes = Vec::new();
+
+        while !matches!(
+            self.current_token_ref().token_type,
+            TokenType::TT_RBRACKET | TokenType::TT_EOF
+        ) {
+            let is_entry = ["page", "link", "section"].iter().any(|keyword| {
+                self.current_token_ref()
+                    .matches(TokenType::TT_KEYWORD, keyword)
+            });
+
+            if !is_entry {
                 return parse_result.failure(Some(StandardError::new(
-                    "expected '}' at end of page definition",
+                    "expected page, link or section",
                     self.current_pos_start(),
                     self.current_pos_end(),
-                    None,
+                    Some("sections hold page blocks, links and other sections"),
                 )));
             }
 
-            parse_result.register_advancement();
-            self.advance();
+            let node = parse_result.register(self.expr());
 
-            if name_val.is_none() || path_val.is_none() || contents_val.is_none() {
-                return parse_result.failure(Some(StandardError::new(
-                    "missing one or more required fields",
-                    pos_start,
-                    pos_end,
-                    Some("add the following required fields 'name', 'path', and 'contents'"),
-                )));
+            if parse_result.error.is_some() {
+                return parse_result;
             }
 
-            return parse_result.success(Some(Page::new(
-                &name_val.unwrap(),
-                &path_val.unwrap(),
-                &contents_val.unwrap(),
-                subpages,
+            nodes.extend(node);
+        }
+
+        if self.current_token_ref().token_type != TokenType::TT_RBRACKET {
+            return parse_result.failure(Some(StandardError::new(
+                "expected '}' at end of section definition",
+                self.current_pos_start(),
+                self.current_pos_end(),
+                None,
             )));
         }
 
-        parse_result.failure(Some(StandardError::new(
-            "unkown keyword",
+        parse_result.register_advancement();
+        self.advance();
+
+        parse_result.success(Some(Node::Section(
+            name_token.value.unwrap_or_default(),
+            nodes,
             pos_start,
-            self.current_pos_end(),
-            None,
         )))
     }
 
+    /// Parses a `link { name: "...", url: "..." }` entry of the navigation.
+    fn link(&mut self, parse_result: &mut ParseResult) -> Result<Link, StandardError> {
+        let pos_start = self.current_pos_start();
+        let keyword_end = self.current_pos_end();
+        let mut name = None;
+        let mut url = None;
+
+        for field in self.block_fields(parse_result, "link", &["name", "url"])? {
+            match field.name.as_str() {
+                "name" => name = Some(field.string()?),
+                _ => url = Some(field.string()?),
+            }
+        }
+
+        let (Some(name), Some(url)) = (name, url) else {
+            return Err(StandardError::new(
+                "missing one or more required fields",
+                pos_start,
+                keyword_end,
+                Some("add the following required fields 'name' and 'url'"),
+            ));
+        };
+
+        let mut link = Link::new(&name, &url);
+        link.pos_start = Some(pos_start);
+
+        Ok(link)
+    }
+
+    /// Parses a nested `name { field: value ... }` block, such as `markdown { }` in `site`.
+    fn block_fields(
+        &mut self,
+        parse_result: &mut ParseResult,
+        block: &str,
+        allowed_fields: &[&str],
+    ) -> Result<Vec<Field>, StandardError> {
+        let pos_start = self.current_pos_start();
+
+        parse_result.register_advancement();
+        self.advance();
+
+        if self.current_token_ref().token_type != TokenType::TT_LBRACKET {
+            return Err(StandardError::new(
+                "expected '{'",
+                pos_start,
+                self.current_pos_end(),
+                Some(&format!("add a '{{' to define the fields of '{block}'")),
+            ));
+        }
+
+        parse_result.register_advancement();
+        self.advance();
+
+        let mut fields = Vec::new();
+
+        while !matches!(
+            self.current_token_ref().token_type,
+            TokenType::TT_RBRACKET | TokenType::TT_EOF
+        ) {
+            fields.push(self.field(parse_result, block, allowed_fields)?);
+        }
+
+        if self.current_token_ref().token_type != TokenType::TT_RBRACKET {
+            return Err(StandardError::new(
+                &format!("expected '}}' at end of {block} definition"),
+                self.current_pos_start(),
+                self.current_pos_end(),
+                None,
+            ));
+        }
+
+        parse_result.register_advancement();
+        self.advance();
+
+        Ok(fields)
+    }
+
+    /// Parses a single `field: value` pair of a `block`, rejecting fields it doesn't allow.
+    /// Blocks without `allowed_fields`, such as `vars { }`, take any name.
+    fn field(
+        &mut self,
+        parse_result: &mut ParseResult,
+        block: &str,
+        allowed_fields: &[&str],
+    ) -> Result<Field, StandardError> {
+        let field_token = self.current_token_copy();
+        let fields_help = if allowed_fields.is_empty() {
+            format!("write the fields of '{block}' as name: \"value\"")
+        } else {
+            format!("fields are {}", list_options(allowed_fields))
+        };
+
+        if !is_name(&field_token) {
+            return Err(StandardError::new(
+                &format!("expected {block} field"),
+                self.current_pos_start(),
+                self.current_pos_end(),
+                Some(&fields_help),
+            ));
+        }
+
+        let field_name = field_token.value.clone().unwrap();
+        let name_pos = (self.current_pos_start(), self.current_pos_end());
+
+        if !allowed_fields.is_empty() && !allowed_fields.contains(&field_name.as_str()) {
+            return Err(StandardError::new(
+                &format!("invalid {block} field '{field_name}'"),
+                self.current_pos_start(),
+                self.current_pos_end(),
+                Some(&fields_help),
+            ));
+        }
+
+        parse_result.register_advancement();
+        self.advance();
+
+        if self.current_token_ref().token_type != TokenType::TT_COLON {
+            return Err(StandardError::new(
+                "missing ':'",
+                self.current_pos_start(),
+                self.current_pos_end(),
+                Some("add a colon to specify the value of the field"),
+            ));
+        }
+
+        parse_result.register_advancement();
+        self.advance();
+
+        let value_token = self.current_token_copy();
+        let value = match value_token.token_type {
+            TokenType::TT_STR => Value::Str(value_token.value.unwrap()),
+            TokenType::TT_NUMBER => match value_token.value.unwrap().parse() {
+                Ok(number) => Value::Number(number),
+                Err(_) => {
+                    return Err(StandardError::new(
+                        "number is too large",
+                        self.current_pos_start(),
+                        self.current_pos_end(),
+                        None,
+                    ));
+                }
+            },
+            TokenType::TT_IDENTIFIER if value_token.value.as_deref() == Some("true") => {
+                Value::Bool(true)
+            }
+            TokenType::TT_IDENTIFIER if value_token.value.as_deref() == Some("false") => {
+                Value::Bool(false)
+            }
+            _ => {
+                return Err(StandardError::new(
+                    "expected value",
+                    self.current_pos_start(),
+                    self.current_pos_end(),
+                    Some("the value of a field must be a string, a number, or true or false"),
+                ));
+            }
+        };
+        let field = Field {
+            name: field_name,
+            name_pos,
+            value,
+            pos_start: self.current_pos_start(),
+            pos_end: self.current_pos_end(),
+        };
+
+        parse_result.register_advancement();
+        self.advance();
+
+        Ok(field)
+    }
+
     fn statement(&mut self) -> ParseResult {
         let mut parse_result = ParseResult::new();
         let pos_start = self.current_pos_start();
@@ -270,8 +1164,7 @@ impl Parser {
 
     fn statements(&mut self) -> ParseResult {
         let mut parse_result = ParseResult::new();
-        let mut statements: Vec<Page> = Vec::new();
-        let pos_start = self.current_pos_start();
+        let mut statements: Vec<Node> = Vec::new();
 
         if self.current_token_ref().token_type == TokenType::TT_EOF {
             return parse_result.success(None);
@@ -300,6 +1193,161 @@ impl Parser {
             statements.push(statement.unwrap());
         }
 
-        parse_result.success(Some(Page::new("", "", "", statements)))
+        parse_result.success(Some(Node::Statements(statements)))
+    }
+}
+
+/// Whether the token can name a field or variable. Keywords only start blocks where a
+/// block can go, so names such as `link` in `palette { }` are still fields.
+fn is_name(token: &Token) -> bool {
+    matches!(
+        token.token_type,
+        TokenType::TT_IDENTIFIER | TokenType::TT_KEYWORD
+    )
+}
+
+/// The value of a number field that only makes sense within `range`, such as a heading
+/// level.
+fn number_in(field: &Field, range: RangeInclusive<i64>) -> Result<i64, StandardError> {
+    let number = field.number()?;
+
+    if !range.contains(&number) {
+        let help = match *range.end() {
+            i64::MAX => format!("'{}' is at least {}", field.name, range.start()),
+            end => format!("'{}' is between {} and {end}", field.name, range.start()),
+        };
+
+        return Err(StandardError::new(
+            &format!("{number} is out of range for '{}'", field.name),
+            field.pos_start.clone(),
+            field.pos_end.clone(),
+            Some(&help),
+        ));
+    }
+
+    Ok(number)
+}
+
+/// The value of a `sort` field.
+fn sort_order(field: &Field) -> Result<SortOrder, StandardError> {
+    let name = field.string()?;
+
+    SortOrder::from_name(&name).ok_or_else(|| {
+        StandardError::new(
+            &format!("unknown sort order '{name}'"),
+            field.pos_start.clone(),
+            field.pos_end.clone(),
+            Some(&format!("sort orders are {}", list_options(&SORT_ORDERS))),
+        )
+    })
+}
+
+/// The value of an `index` field.
+fn index_style(field: &Field) -> Result<IndexStyle, StandardError> {
+    let name = field.string()?;
+
+    IndexStyle::from_name(&name).ok_or_else(|| {
+        StandardError::new(
+            &format!("unknown index style '{name}'"),
+            field.pos_start.clone(),
+            field.pos_end.clone(),
+            Some(&format!("index styles are {}", list_options(&INDEX_STYLES))),
+        )
+    })
+}
+
+/// The value of a field holding several strings, such as `keywords`, written as one string
+/// separated by commas.
+fn comma_separated(field: &Field) -> Result<Vec<String>, StandardError> {
+    Ok(field
+        .string()?
+        .split(',')
+        .map(str::trim)
+        .filter(|keyword| !keyword.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_str;
+
+    #[test]
+    fn keywords_can_name_fields() {
+        let site = parse_str(
+            "test.dl",
+            r##"
+            site { palette { link: "#8250df" } }
+            vars { link: "docs" }
+            let section = "guides"
+            page { name: "${link} ${section}" path: "/" contents: "x" }
+            "##,
+        )
+        .unwrap();
+
+        assert_eq!(site.config.palette["link"], "#8250df");
+        assert_eq!(site.pages[0].name, "docs guides");
+    }
+
+    fn site_error(config: &str) -> String {
+        let code =
+            format!("site {{ {config} }}\npage {{ name: \"Home\" path: \"/\" contents: \"\" }}");
+
+        parse_str("test.dl", &code).unwrap_err().text.clone()
+    }
+
+    #[test]
+    fn out_of_range_site_numbers_are_rejected() {
+        assert_eq!(
+            site_error("navigation_depth: 0"),
+            "0 is out of range for 'navigation_depth'"
+        );
+        assert_eq!(
+            site_error("toc_min_level: 0"),
+            "0 is out of range for 'toc_min_level'"
+        );
+        assert_eq!(
+            site_error("toc_max_level: 300"),
+            "300 is out of range for 'toc_max_level'"
+        );
+    }
+
+    #[test]
+    fn inverted_toc_levels_are_rejected() {
+        assert_eq!(
+            site_error("toc_min_level: 4 toc_max_level: 2"),
+            "toc_min_level 4 is above toc_max_level 2"
+        );
+        // the default maximum is 3
+        assert_eq!(
+            site_error("toc_min_level: 5"),
+            "toc_min_level 5 is above toc_max_level 3"
+        );
+
+        let site = parse_str("test.dl", "site { toc_min_level: 3 toc_max_level: 3 }").unwrap();
+
+        assert_eq!(site.config.toc_levels(), (3, 3));
+    }
+
+    #[test]
+    fn orders_can_be_negative() {
+        let site = parse_str(
+            "test.dl",
+            r#"page { name: "First" path: "/" contents: "" order: -1 }"#,
+        )
+        .unwrap();
+
+        assert_eq!(site.pages[0].order, Some(-1));
+        assert!(site.to_dl().contains("order: -1"));
+        assert_eq!(
+            site_error("navigation_depth: -2"),
+            "-2 is out of range for 'navigation_depth'"
+        );
+        assert_eq!(
+            parse_str("test.dl", "page { order: - 1 }")
+                .unwrap_err()
+                .text,
+            "unknown character '-'"
+        );
     }
 }
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+/// The kinds of redirect files, as written in the `redirects { }` block of a site.
+pub const REDIRECT_OUTPUTS: [&str; 3] = ["stubs", "netlify", "nginx"];
+
+/// Which files the `redirect_from` paths of pages are written to. Stub pages work on any
+/// host and are on unless turned off; the files for servers that redirect on their own are
+/// off unless turned on in the `redirects { }` block of the `site` block.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RedirectOutputs {
+    /// A page at each old path that sends the reader on with a meta refresh.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) stubs: Option<bool>,
+    /// `_redirects`, read by Netlify and Cloudflare Pages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) netlify: Option<bool>,
+    /// `redirects.map`, a `map` block for nginx.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) nginx: Option<bool>,
+}
+
+impl RedirectOutputs {
+    /// Whether the output called `name` is on, see [`REDIRECT_OUTPUTS`].
+    pub fn enabled(&self, name: &str) -> bool {
+        self.setting(name).unwrap_or(name == "stubs")
+    }
+
+    /// The outputs set explicitly, in the order of [`REDIRECT_OUTPUTS`].
+    pub(crate) fn settings(&self) -> Vec<(&'static str, bool)> {
+        REDIRECT_OUTPUTS
+            .iter()
+            .filter_map(|name| Some((*name, self.setting(name)?)))
+            .collect()
+    }
+
+    pub(crate) fn set(&mut self, name: &str, enabled: bool) {
+        let setting = match name {
+            "stubs" => &mut self.stubs,
+            "netlify" => &mut self.netlify,
+            "nginx" => &mut self.nginx,
+            _ => return,
+        };
+
+        *setting = Some(enabled);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.settings().is_empty()
+    }
+
+    fn setting(&self, name: &str) -> Option<bool> {
+        match name {
+            "stubs" => self.stubs,
+            "netlify" => self.netlify,
+            "nginx" => self.nginx,
+            _ => None,
+        }
+    }
+}
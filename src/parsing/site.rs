@@ -0,0 +1,383 @@
+use crate::{
+    building::serializer::Serializer,
+    errors::standard_error::StandardError,
+    lexing::position::Position,
+    parsing::{
+        link::Link, nav_entry::NavEntry, node::Node, page::Page, site_config::SiteConfig,
+        snippet::Snippet, variable::Variable,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// A documentation site: the tree of pages declared in a `.dl` file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Site {
+    #[serde(default)]
+    pub(crate) config: SiteConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) variables: Vec<Variable>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) snippets: Vec<Snippet>,
+    #[serde(default)]
+    pub(crate) pages: Vec<Page>,
+    /// The sidebar laid out by a `nav { }` block, in place of the page tree.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) nav: Option<Vec<NavEntry>>,
+    /// Whether the navigation comes from links and sections declared between the pages
+    /// instead of a `nav` block, which makes it follow the order of the pages.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub(crate) declared_nav: bool,
+    /// The `.dl` file the site was parsed from, which relative paths are resolved against.
+    #[serde(skip)]
+    pub(crate) filename: String,
+}
+
+impl Site {
+    pub fn new(pages: Vec<Page>) -> Self {
+        Self {
+            pages,
+            ..Default::default()
+        }
+    }
+
+    /// Assembles a site from the top level statements of a parsed file.
+    pub(crate) fn from_statements(
+        filename: &str,
+        statements: Vec<Node>,
+    ) -> Result<Self, StandardError> {
+        let mut site = Self {
+            filename: filename.to_string(),
+            ..Default::default()
+        };
+        let mut has_config = false;
+        let mut declared = Vec::new();
+        let mut declares_entries = false;
+        // where the first link or section outside of a nav block is declared
+        let mut first_entry: Option<Position> = None;
+
+        for statement in statements {
+            match statement {
+                Node::Page(_) | Node::Link(_) | Node::Section(..) => {
+                    let pos_start = match &statement {
+                        Node::Link(Link { pos_start, .. }) => pos_start.clone(),
+                        Node::Section(_, _, pos_start) => Some(pos_start.clone()),
+                        _ => None,
+                    };
+
+                    declares_entries |= !matches!(statement, Node::Page(_));
+                    first_entry = first_entry.or(pos_start);
+                    declared.push(site.declare(statement));
+                }
+                Node::Site(config) => {
+                    if has_config {
+                        let pos_start = config.pos_start.clone().unwrap();
+                        let mut pos_end = pos_start.clone();
+                        pos_end.advance(None);
+
+                        return Err(StandardError::new(
+                            "duplicate site definition",
+                            pos_start,
+                            pos_end,
+                            Some("merge the settings into a single 'site' block"),
+                        ));
+                    }
+
+                    site.config = config;
+                    has_config = true;
+                }
+                Node::Snippet(snippet) => {
+                    if site.snippet(&snippet.name).is_some() {
+                        let (pos_start, pos_end) = snippet.name_range();
+
+                        return Err(StandardError::new(
+                            &format!("duplicate snippet '{}'", snippet.name),
+                            pos_start,
+                            pos_end,
+                            Some("give each snippet a different name"),
+                        ));
+                    }
+
+                    site.snippets.push(snippet);
+                }
+                Node::Variables(variables) => {
+                    for variable in variables {
+                        if site.variable(&variable.name).is_some() {
+                            let (pos_start, pos_end) = variable.name_pos.clone().unwrap();
+
+                            return Err(StandardError::new(
+                                &format!("duplicate variable '{}'", variable.name),
+                                pos_start,
+                                pos_end,
+                                Some("declare each variable once"),
+                            ));
+                        }
+
+                        site.variables.push(variable);
+                    }
+                }
+                Node::Nav(entries, pos_start) => {
+                    if site.nav.is_some() {
+                        let mut pos_end = pos_start.clone();
+                        pos_end.advance(None);
+
+                        return Err(StandardError::new(
+                            "duplicate nav definition",
+                            pos_start,
+                            pos_end,
+                            Some("merge the entries into a single 'nav' block"),
+                        ));
+                    }
+
+                    site.nav = Some(entries);
+                }
+                Node::Statements(_) => {}
+            }
+        }
+
+        if declares_entries {
+            if site.nav.is_some() {
+                let pos_start = first_entry.unwrap_or_else(|| Position::new(0, 0, 0, filename, ""));
+                let mut pos_end = pos_start.clone();
+                pos_end.advance(None);
+
+                return Err(StandardError::new(
+                    "links and sections outside of the nav block",
+                    pos_start,
+                    pos_end,
+                    Some("a site with a nav block lists its links and sections in it"),
+                ));
+            }
+
+            site.nav = Some(declared);
+            site.declared_nav = true;
+        }
+
+        Ok(site)
+    }
+
+    /// Adds the pages of a top level page, link or section to the site, returning its entry
+    /// in the navigation.
+    fn declare(&mut self, node: Node) -> NavEntry {
+        match node {
+            Node::Page(page) => {
+                let entry = NavEntry::Page {
+                    path: page.path.clone(),
+                    name: None,
+                    path_pos: None,
+                };
+
+                self.pages.push(page);
+                entry
+            }
+            Node::Section(name, nodes, pos_start) => NavEntry::Section {
+                name,
+                entries: nodes.into_iter().map(|node| self.declare(node)).collect(),
+                pos_start: Some(pos_start),
+            },
+            Node::Link(link) => NavEntry::Link(link),
+            _ => unreachable!("only pages, links and sections are declared"),
+        }
+    }
+
+    pub fn config(&self) -> &SiteConfig {
+        &self.config
+    }
+
+    /// The variables declared in the file, with the values they ended up with.
+    pub fn variables(&self) -> &[Variable] {
+        &self.variables
+    }
+
+    pub fn variable(&self, name: &str) -> Option<&Variable> {
+        self.variables.iter().find(|variable| variable.name == name)
+    }
+
+    /// The snippets declared in the file, in declaration order.
+    pub fn snippets(&self) -> &[Snippet] {
+        &self.snippets
+    }
+
+    pub fn snippet(&self, name: &str) -> Option<&Snippet> {
+        self.snippets.iter().find(|snippet| snippet.name == name)
+    }
+
+    /// The top level pages of the site, in declaration order.
+    pub fn pages(&self) -> &[Page] {
+        &self.pages
+    }
+
+    /// The entries of the `nav { }` block, if the site has one.
+    pub fn nav(&self) -> Option<&[NavEntry]> {
+        self.nav.as_deref()
+    }
+
+    /// Every page of the site, depth first.
+    pub fn iter(&self) -> impl Iterator<Item = &Page> {
+        self.pages.iter().flat_map(|page| page.iter())
+    }
+
+    /// Looks up a page anywhere in the tree by its path.
+    pub fn find(&self, path: &str) -> Option<&Page> {
+        self.iter().find(|page| page.path == path)
+    }
+
+    /// Orders the top level pages by the `sort` of the site and the subpages of every page
+    /// by its own `sort`. Links and sections declared between the pages are sorted along
+    /// with them, and the pages then follow the navigation, so that it, previous/next links
+    /// and search all agree.
+    pub(crate) fn sort_pages(&mut self) {
+        fn sort_subpages(pages: &mut [Page]) {
+            for page in pages {
+                page.sort().sort(&mut page.subpages);
+                sort_subpages(&mut page.subpages);
+            }
+        }
+
+        let sort = self.config.sort();
+
+        sort.sort(&mut self.pages);
+
+        if self.declared_nav
+            && let Some(nav) = &mut self.nav
+        {
+            sort.sort_entries(nav, &self.pages);
+
+            let paths = nav
+                .iter()
+                .flat_map(|entry| entry.iter())
+                .filter_map(|entry| match entry {
+                    NavEntry::Page { path, .. } => Some(path.as_str()),
+                    NavEntry::Section { .. } | NavEntry::Link(_) => None,
+                })
+                .collect::<Vec<_>>();
+
+            self.pages
+                .sort_by_key(|page| paths.iter().position(|path| *path == page.path));
+        }
+
+        sort_subpages(&mut self.pages);
+    }
+
+    /// Writes the site out as canonical `.dl` source.
+    pub fn to_dl(&self) -> String {
+        Serializer::new().serialize(self)
+    }
+
+    /// Resolves a path written in the `.dl` file relative to the file's directory.
+    pub(crate) fn resolve_path(&self, path: &str) -> PathBuf {
+        Path::new(&self.filename)
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join(path)
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_str, parsing::nav_entry::NavEntry};
+
+    const SITE: &str = r#"
+        page { name: "Later" path: "/later" contents: "" }
+
+        section "Guides" {
+            page { name: "Install" path: "/install" contents: "" order: 2 }
+            page { name: "Deploy" path: "/deploy" contents: "" order: 1 }
+        }
+
+        page { name: "First" path: "/" contents: "" order: 1 }
+    "#;
+
+    fn nav_paths(entries: &[NavEntry]) -> Vec<&str> {
+        entries
+            .iter()
+            .flat_map(|entry| entry.iter())
+            .filter_map(|entry| match entry {
+                NavEntry::Page { path, .. } => Some(path.as_str()),
+                NavEntry::Section { .. } | NavEntry::Link(_) => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn declared_nav_follows_the_sort() {
+        let mut site = parse_str("test.dl", SITE).unwrap();
+        site.sort_pages();
+
+        let pages = site
+            .pages
+            .iter()
+            .map(|page| page.path.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            nav_paths(site.nav().unwrap()),
+            ["/", "/later", "/deploy", "/install"]
+        );
+        assert_eq!(pages, ["/", "/later", "/deploy", "/install"]);
+    }
+
+    #[test]
+    fn declared_entries_make_up_the_nav() {
+        let site = parse_str(
+            "test.dl",
+            r#"
+            link { name: "GitHub" url: "https://github.com" }
+            page { name: "Home" path: "/" contents: "" }
+            "#,
+        )
+        .unwrap();
+
+        assert!(site.declared_nav);
+        assert!(matches!(
+            site.nav().unwrap(),
+            [NavEntry::Link(link), NavEntry::Page { .. }] if link.url == "https://github.com"
+        ));
+
+        let site = parse_str("test.dl", r#"page { name: "Home" path: "/" contents: "" }"#);
+
+        assert!(site.unwrap().nav().is_none());
+    }
+
+    #[test]
+    fn declared_entries_cannot_be_combined_with_a_nav_block() {
+        let error = parse_str(
+            "test.dl",
+            r#"
+            nav { page "/" }
+            page { name: "Home" path: "/" contents: "" }
+            section "Guides" { link { name: "GitHub" url: "https://github.com" } }
+            "#,
+        )
+        .unwrap_err();
+
+        assert_eq!(error.text, "links and sections outside of the nav block");
+        assert_eq!(error.pos_start.line_num, 3);
+    }
+
+    #[test]
+    fn links_need_a_name_and_url() {
+        let error = parse_str("test.dl", r#"link { name: "GitHub" }"#).unwrap_err();
+
+        assert_eq!(error.text, "missing one or more required fields");
+    }
+
+    #[test]
+    fn declared_nav_survives_a_round_trip() {
+        let site = parse_str("test.dl", SITE).unwrap();
+        let dl = site.to_dl();
+        let mut parsed = parse_str("test.dl", &dl).unwrap();
+        parsed.sort_pages();
+
+        assert!(!dl.contains("nav {"));
+        assert!(parsed.declared_nav);
+        assert_eq!(
+            nav_paths(parsed.nav().unwrap()),
+            ["/", "/later", "/deploy", "/install"]
+        );
+    }
+}
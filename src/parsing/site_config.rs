@@ -0,0 +1,187 @@
+use crate::{
+    lexing::position::Position,
+    parsing::{
+        markdown_extensions::MarkdownExtensions, redirect_outputs::RedirectOutputs,
+        sort_order::SortOrder,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const DEFAULT_TOC_MIN_LEVEL: u8 = 2;
+pub const DEFAULT_TOC_MAX_LEVEL: u8 = 3;
+
+/// The colors of the built in theme that the `palette { }` block of a site can change. Each
+/// sets both themes, unless it's followed by the same name ending in `_dark`.
+pub const PALETTE_COLORS: [&str; 10] = [
+    "background",
+    "foreground",
+    "link",
+    "border",
+    "selection",
+    "note",
+    "tip",
+    "important",
+    "warning",
+    "caution",
+];
+
+/// Site wide settings, declared once in a `site { }` block.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SiteConfig {
+    /// Directory of templates that override the built in ones, relative to the `.dl` file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) templates: Option<String>,
+    /// How many levels of the page tree the sidebar shows, all of them when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) navigation_depth: Option<usize>,
+    /// The range of heading levels listed in the table of contents, `2` to `3` by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) toc_min_level: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) toc_max_level: Option<u8>,
+    /// Whether a search index and search box are generated, `true` by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) search: Option<bool>,
+    /// Whether code blocks show line numbers unless they say otherwise, `false` by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) line_numbers: Option<bool>,
+    /// How the top level pages are ordered, by weight when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) sort: Option<SortOrder>,
+    /// Where the site is served from, such as `https://docs.example.com`, which canonical
+    /// links and link previews need to be absolute.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) url: Option<String>,
+    /// The description, keywords, author and image of pages that don't set their own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) description: Option<String>,
+    #[serde(skip)]
+    pub(crate) description_range: Option<(Position, Position)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) keywords: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) image: Option<String>,
+    /// Whether `sitemap.xml` is generated, `true` by default. It needs `url` to be set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) sitemap: Option<bool>,
+    /// Whether `robots.txt` is generated, `true` by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) robots: Option<bool>,
+    /// The contents of `robots.txt`, replacing the default rules allowing everything.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) robots_txt: Option<String>,
+    /// Whether `404.html` is generated, `true` by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) not_found: Option<bool>,
+    #[serde(default, skip_serializing_if = "MarkdownExtensions::is_empty")]
+    pub(crate) markdown: MarkdownExtensions,
+    #[serde(default, skip_serializing_if = "RedirectOutputs::is_empty")]
+    pub(crate) redirects: RedirectOutputs,
+    /// Colors replacing the ones of the built in theme, keyed by [`PALETTE_COLORS`].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub(crate) palette: BTreeMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) pos_start: Option<Position>,
+}
+
+impl SiteConfig {
+    pub fn templates(&self) -> Option<&str> {
+        self.templates.as_deref()
+    }
+
+    pub fn navigation_depth(&self) -> Option<usize> {
+        self.navigation_depth
+    }
+
+    pub fn toc_levels(&self) -> (u8, u8) {
+        (
+            self.toc_min_level.unwrap_or(DEFAULT_TOC_MIN_LEVEL),
+            self.toc_max_level.unwrap_or(DEFAULT_TOC_MAX_LEVEL),
+        )
+    }
+
+    pub fn search(&self) -> bool {
+        self.search.unwrap_or(true)
+    }
+
+    pub fn line_numbers(&self) -> bool {
+        self.line_numbers.unwrap_or(false)
+    }
+
+    pub fn sort(&self) -> SortOrder {
+        self.sort.unwrap_or_default()
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn keywords(&self) -> &[String] {
+        &self.keywords
+    }
+
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    pub fn image(&self) -> Option<&str> {
+        self.image.as_deref()
+    }
+
+    pub fn sitemap(&self) -> bool {
+        self.sitemap.unwrap_or(true)
+    }
+
+    pub fn robots(&self) -> bool {
+        self.robots.unwrap_or(true)
+    }
+
+    pub fn robots_txt(&self) -> Option<&str> {
+        self.robots_txt.as_deref()
+    }
+
+    pub fn not_found(&self) -> bool {
+        self.not_found.unwrap_or(true)
+    }
+
+    pub fn markdown(&self) -> &MarkdownExtensions {
+        &self.markdown
+    }
+
+    pub fn redirects(&self) -> &RedirectOutputs {
+        &self.redirects
+    }
+
+    pub fn palette(&self) -> &BTreeMap<String, String> {
+        &self.palette
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.templates.is_none()
+            && self.navigation_depth.is_none()
+            && self.toc_min_level.is_none()
+            && self.toc_max_level.is_none()
+            && self.search.is_none()
+            && self.line_numbers.is_none()
+            && self.sort.is_none()
+            && self.url.is_none()
+            && self.description.is_none()
+            && self.keywords.is_empty()
+            && self.author.is_none()
+            && self.image.is_none()
+            && self.sitemap.is_none()
+            && self.robots.is_none()
+            && self.robots_txt.is_none()
+            && self.not_found.is_none()
+            && self.markdown.is_empty()
+            && self.redirects.is_empty()
+            && self.palette.is_empty()
+    }
+}
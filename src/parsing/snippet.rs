@@ -0,0 +1,93 @@
+use crate::lexing::position::Position;
+use serde::{Deserialize, Serialize};
+
+/// A piece of Markdown declared once with `snippet "name" { contents: "..." }` and inlined
+/// into pages with `{{#snippet name}}`. `{{parameter}}` placeholders in the contents are
+/// filled in from the reference, e.g. `{{#snippet install version=2.3}}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snippet {
+    pub(crate) name: String,
+    #[serde(rename = "contents")]
+    pub(crate) markdown_contents: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) pos_start: Option<Position>,
+    /// Where the name of the snippet is written, to point at unused snippets.
+    #[serde(skip)]
+    pub(crate) name_pos: Option<(Position, Position)>,
+    #[serde(skip)]
+    pub(crate) contents_pos: Option<Position>,
+}
+
+impl Snippet {
+    pub fn new(name: &str, markdown_contents: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            markdown_contents: markdown_contents.to_string(),
+            pos_start: None,
+            name_pos: None,
+            contents_pos: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The raw Markdown contents, exactly as written in the source.
+    pub fn contents(&self) -> &str {
+        &self.markdown_contents
+    }
+
+    /// The names of the `{{parameter}}` placeholders in the contents, in order of appearance.
+    pub fn parameters(&self) -> Vec<&str> {
+        let mut parameters = Vec::new();
+        let mut rest = self.markdown_contents.as_str();
+
+        while let Some(start) = rest.find("{{") {
+            rest = &rest[start + 2..];
+
+            let Some(end) = rest.find("}}") else {
+                break;
+            };
+            let name = &rest[..end];
+
+            if is_parameter_name(name) && !parameters.contains(&name) {
+                parameters.push(name);
+            }
+        }
+
+        parameters
+    }
+
+    /// The range of the name of the snippet, or of the start of its block.
+    pub(crate) fn name_range(&self) -> (Position, Position) {
+        if let Some(name_pos) = &self.name_pos {
+            return name_pos.clone();
+        }
+
+        let pos_start = self
+            .pos_start
+            .clone()
+            .unwrap_or_else(|| Position::new(0, 0, 0, "<unknown>", ""));
+        let mut pos_end = pos_start.clone();
+
+        pos_end.advance(None);
+
+        (pos_start, pos_end)
+    }
+
+    /// The range of `text` on the given line of the contents, see [`Position::range_in_string`].
+    pub(crate) fn contents_range(&self, line: usize, text: &str) -> (Position, Position) {
+        match &self.contents_pos {
+            Some(contents_pos) => contents_pos.range_in_string(line, text),
+            None => self.name_range(),
+        }
+    }
+}
+
+pub(crate) fn is_parameter_name(name: &str) -> bool {
+    name.starts_with(|character: char| character.is_ascii_alphabetic() || character == '_')
+        && name
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || character == '_')
+}
@@ -0,0 +1,113 @@
+use crate::parsing::{nav_entry::NavEntry, page::Page};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// The names `sort` takes, in the order they're listed in errors.
+pub const SORT_ORDERS: [&str; 3] = ["weight", "title", "date"];
+
+/// How the pages under a parent are ordered, set with `sort` on the parent page, or on the
+/// site for the top level pages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// Pages with an `order` first, lowest first, then the rest as declared.
+    #[default]
+    Weight,
+    /// Alphabetically by name, ignoring case.
+    Title,
+    /// Newest `date` first, then the pages without one as declared.
+    Date,
+}
+
+impl SortOrder {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "weight" => Some(Self::Weight),
+            "title" => Some(Self::Title),
+            "date" => Some(Self::Date),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Weight => "weight",
+            Self::Title => "title",
+            Self::Date => "date",
+        }
+    }
+
+    /// Sorts `pages` in place. The sort is stable, so ties keep their declaration order.
+    pub fn sort(&self, pages: &mut [Page]) {
+        pages.sort_by(|a, b| self.compare(&SortKey::page(a), &SortKey::page(b)));
+    }
+
+    /// Sorts navigation entries declared between the top level `pages` like the pages
+    /// themselves, along with the entries of each section. Links and sections have neither
+    /// an order nor a date, so they're only placed by their name.
+    pub(crate) fn sort_entries(&self, entries: &mut [NavEntry], pages: &[Page]) {
+        entries.sort_by(|a, b| self.compare(&SortKey::entry(a, pages), &SortKey::entry(b, pages)));
+
+        for entry in entries {
+            if let NavEntry::Section { entries, .. } = entry {
+                self.sort_entries(entries, pages);
+            }
+        }
+    }
+
+    fn compare(&self, a: &SortKey, b: &SortKey) -> Ordering {
+        match self {
+            Self::Weight => match (a.order, b.order) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+            Self::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+            Self::Date => match (&a.date, &b.date) {
+                (Some(a), Some(b)) => b.cmp(a),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+        }
+    }
+}
+
+/// What is sorted on of a page or navigation entry.
+struct SortKey<'a> {
+    order: Option<i64>,
+    title: &'a str,
+    date: Option<&'a str>,
+}
+
+impl<'a> SortKey<'a> {
+    fn page(page: &'a Page) -> Self {
+        Self {
+            order: page.order,
+            title: &page.name,
+            date: page.date.as_deref(),
+        }
+    }
+
+    /// The key of the page an entry points to, or of its name if it isn't a page.
+    fn entry(entry: &'a NavEntry, pages: &'a [Page]) -> Self {
+        match entry {
+            NavEntry::Page { path, .. } => pages
+                .iter()
+                .find(|page| page.path == *path)
+                .map(Self::page)
+                .unwrap_or_else(|| Self::named(path)),
+            NavEntry::Section { name, .. } => Self::named(name),
+            NavEntry::Link(link) => Self::named(&link.name),
+        }
+    }
+
+    fn named(title: &'a str) -> Self {
+        Self {
+            order: None,
+            title,
+            date: None,
+        }
+    }
+}
@@ -0,0 +1,17 @@
+/// The value of a field in a block.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Str(String),
+    Number(i64),
+    Bool(bool),
+}
+
+impl Value {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Value::Str(_) => "string",
+            Value::Number(_) => "number",
+            Value::Bool(_) => "boolean",
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use crate::lexing::position::Position;
+use serde::{Deserialize, Serialize};
+
+/// A value declared with `let name = "value"` or in a `vars { }` block, which `${name}`
+/// stands for in any string of the file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Variable {
+    pub(crate) name: String,
+    pub(crate) value: String,
+    /// Where the name of the variable is written.
+    #[serde(skip)]
+    pub(crate) name_pos: Option<(Position, Position)>,
+}
+
+impl Variable {
+    pub fn new(name: &str, value: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            value: value.to_string(),
+            name_pos: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
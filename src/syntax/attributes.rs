@@ -1,3 +1,6 @@
+pub const DIGITS: &str = "0123456789";
 pub const LETTERS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ_";
 pub const LETTERS_DIGITS: &str = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ_";
-pub const KEYWORDS: &[&str] = &["page"];
+pub const KEYWORDS: &[&str] = &[
+    "page", "site", "snippet", "let", "vars", "nav", "link", "section",
+];
@@ -0,0 +1,340 @@
+use crate::{
+    errors::standard_error::{StandardError, closest_match},
+    lexing::position::Position,
+    parsing::{condition::Condition, nav_entry::NavEntry, page::Page, site::Site},
+};
+use std::collections::{HashMap, HashSet};
+
+/// Checks the rules every site must satisfy before it can be generated, whether it was
+/// parsed from a `.dl` file or put together with a `SiteBuilder`.
+pub struct Validator {
+    pub seen_paths: HashSet<String>,
+}
+
+impl Validator {
+    pub fn new() -> Self {
+        Self {
+            seen_paths: HashSet::new(),
+        }
+    }
+
+    pub fn validate(&mut self, site: &Site) -> Result<(), StandardError> {
+        self.validate_pages(&site.pages)?;
+        self.validate_redirects(site)?;
+
+        if let Some(nav) = &site.nav {
+            self.validate_nav(nav)?;
+        }
+
+        Ok(())
+    }
+
+    /// Makes sure every page the `nav` block lists exists, and is listed only once.
+    fn validate_nav(&self, nav: &[NavEntry]) -> Result<(), StandardError> {
+        let mut listed = HashSet::new();
+
+        for entry in nav.iter().flat_map(|entry| entry.iter()) {
+            let NavEntry::Page { path, path_pos, .. } = entry else {
+                continue;
+            };
+            let (pos_start, pos_end) = path_pos.clone().unwrap_or_else(unknown_range);
+            let trimmed = path.trim_end_matches('/');
+
+            if !self.seen_paths.contains(trimmed) {
+                let paths = self
+                    .seen_paths
+                    .iter()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>();
+                let help = match closest_match(trimmed, &paths) {
+                    Some("") => "did you mean '/'?".to_string(),
+                    Some(suggestion) => format!("did you mean '{suggestion}'?"),
+                    None => "list the path of a page declared in the file".to_string(),
+                };
+
+                return Err(StandardError::new(
+                    &format!("no page at '{path}' for the nav entry"),
+                    pos_start,
+                    pos_end,
+                    Some(&help),
+                ));
+            }
+
+            if !listed.insert(trimmed) {
+                return Err(StandardError::new(
+                    &format!("page '{path}' is listed more than once in the nav block"),
+                    pos_start,
+                    pos_end,
+                    Some("list each page once"),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Makes sure every old path of a page is a path, and redirects to that page alone
+    /// instead of replacing another page.
+    fn validate_redirects(&self, site: &Site) -> Result<(), StandardError> {
+        let mut redirects: HashMap<&str, &Page> = HashMap::new();
+
+        for page in site.iter() {
+            for old_path in &page.redirect_from {
+                let (pos_start, pos_end) = page.redirect_from_range();
+                let trimmed = old_path.trim_end_matches('/');
+
+                if !old_path.starts_with('/') {
+                    return Err(StandardError::new(
+                        &format!(
+                            "invalid redirect path '{old_path}' for page '{}'",
+                            page.name
+                        ),
+                        pos_start,
+                        pos_end,
+                        Some("old paths must start with a slash '/'"),
+                    ));
+                }
+
+                if !has_plain_segments(old_path) {
+                    return Err(StandardError::new(
+                        &format!(
+                            "invalid redirect path '{old_path}' for page '{}'",
+                            page.name
+                        ),
+                        pos_start,
+                        pos_end,
+                        Some("old paths can't contain empty, '.' or '..' segments"),
+                    ));
+                }
+
+                if trimmed == page.path.trim_end_matches('/') {
+                    return Err(StandardError::new(
+                        &format!("page '{}' redirects from its own path", page.name),
+                        pos_start,
+                        pos_end,
+                        Some(&format!("remove '{old_path}' from redirect_from")),
+                    ));
+                }
+
+                if self.seen_paths.contains(trimmed) {
+                    return Err(StandardError::new(
+                        &format!("redirect from '{old_path}' collides with the page at that path"),
+                        pos_start,
+                        pos_end,
+                        Some(&format!(
+                            "a page is built at '{old_path}', so it can't redirect to '{}'",
+                            page.path
+                        )),
+                    ));
+                }
+
+                if let Some(other) = redirects.insert(trimmed, page)
+                    && other.path != page.path
+                {
+                    return Err(StandardError::new(
+                        &format!(
+                            "'{old_path}' redirects to both '{}' and '{}'",
+                            other.path, page.path
+                        ),
+                        pos_start,
+                        pos_end,
+                        Some("each old path can only redirect to one page"),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_pages(&mut self, pages: &[Page]) -> Result<(), StandardError> {
+        for page in pages {
+            let (pos_start, pos_end) = page.keyword_range();
+
+            if !page.path.starts_with('/') {
+                return Err(StandardError::new(
+                    &format!("invalid path '{}' for page '{}'", page.path, page.name),
+                    pos_start,
+                    pos_end,
+                    Some("the path of a page must start with a slash '/'"),
+                ));
+            }
+
+            if !has_plain_segments(&page.path) {
+                return Err(StandardError::new(
+                    &format!("invalid path '{}' for page '{}'", page.path, page.name),
+                    pos_start,
+                    pos_end,
+                    Some("paths can't contain empty, '.' or '..' segments"),
+                ));
+            }
+
+            if !self
+                .seen_paths
+                .insert(page.path.trim_end_matches('/').to_string())
+            {
+                return Err(StandardError::new(
+                    &format!("duplicate path '{}' for page '{}'", page.path, page.name),
+                    pos_start,
+                    pos_end,
+                    Some("every page must have its own path"),
+                ));
+            }
+
+            if let Some(date) = &page.date
+                && !is_date(date)
+            {
+                let (pos_start, pos_end) = page.date_range();
+
+                return Err(StandardError::new(
+                    &format!("invalid date '{date}' for page '{}'", page.name),
+                    pos_start,
+                    pos_end,
+                    Some("write dates as YYYY-MM-DD, e.g. \"2024-05-01\""),
+                ));
+            }
+
+            if let Some(condition) = &page.condition
+                && let Err((text, help)) = Condition::parse(condition)
+            {
+                let (pos_start, pos_end) = page.condition_range();
+
+                return Err(StandardError::new(&text, pos_start, pos_end, Some(&help)));
+            }
+
+            self.validate_pages(&page.subpages)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether every segment of `path` names a directory of its own, so that nothing written
+/// for it can end up outside of the output directory.
+fn has_plain_segments(path: &str) -> bool {
+    let inner = path.strip_prefix('/').unwrap_or(path);
+    let inner = inner.strip_suffix('/').unwrap_or(inner);
+
+    inner.is_empty()
+        || inner
+            .split('/')
+            .all(|segment| !matches!(segment, "" | "." | ".."))
+}
+
+/// Whether `date` is written as `YYYY-MM-DD`, so that dates sort like the strings they are.
+fn is_date(date: &str) -> bool {
+    date.len() == 10
+        && date.char_indices().all(|(index, character)| match index {
+            4 | 7 => character == '-',
+            _ => character.is_ascii_digit(),
+        })
+}
+
+fn unknown_range() -> (Position, Position) {
+    let pos_start = Position::new(0, 0, 0, "<unknown>", "");
+    let mut pos_end = pos_start.clone();
+    pos_end.advance(None);
+
+    (pos_start, pos_end)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_str;
+
+    const PAGES: &str = r#"
+        page { name: "Home" path: "/" contents: "" }
+        page { name: "Install" path: "/install" contents: "" }
+    "#;
+
+    fn nav_error(nav: &str) -> (String, Option<String>) {
+        let error = parse_str("test.dl", &format!("nav {{ {nav} }}\n{PAGES}")).unwrap_err();
+
+        (error.text.clone(), error.help.clone())
+    }
+
+    #[test]
+    fn nav_entries_must_name_pages() {
+        let (text, help) = nav_error(r#"page "/instal""#);
+
+        assert_eq!(text, "no page at '/instal' for the nav entry");
+        assert_eq!(help.as_deref(), Some("did you mean '/install'?"));
+    }
+
+    #[test]
+    fn nav_entries_are_listed_once() {
+        let (text, _) = nav_error(r#"page "/install" section "Again" { page "/install/" }"#);
+
+        assert_eq!(
+            text,
+            "page '/install/' is listed more than once in the nav block"
+        );
+    }
+
+    #[test]
+    fn page_paths_stay_in_the_output_directory() {
+        for path in ["/../../tmp/x", "/docs/../..", "/a//b", "/./a"] {
+            let code = format!("page {{ name: \"Escape\" path: \"{path}\" contents: \"\" }}");
+
+            assert_eq!(
+                parse_str("test.dl", &code).unwrap_err().text,
+                format!("invalid path '{path}' for page 'Escape'")
+            );
+        }
+
+        assert!(parse_str("test.dl", PAGES).is_ok());
+    }
+
+    fn redirect_error(redirect_from: &str) -> String {
+        let code = format!(
+            "{PAGES}\npage {{ name: \"Moved\" path: \"/moved\" contents: \"\" redirect_from: \"{redirect_from}\" }}"
+        );
+
+        parse_str("test.dl", &code).unwrap_err().text.clone()
+    }
+
+    #[test]
+    fn redirects_cannot_collide() {
+        assert_eq!(
+            redirect_error("/install/"),
+            "redirect from '/install/' collides with the page at that path"
+        );
+        assert_eq!(
+            redirect_error("/moved"),
+            "page 'Moved' redirects from its own path"
+        );
+        assert_eq!(
+            redirect_error("old"),
+            "invalid redirect path 'old' for page 'Moved'"
+        );
+
+        let code = format!(
+            "{PAGES}\npage {{ name: \"A\" path: \"/a\" contents: \"\" redirect_from: \"/old\" }}\npage {{ name: \"B\" path: \"/b\" contents: \"\" redirect_from: \"/old/\" }}"
+        );
+
+        assert_eq!(
+            parse_str("test.dl", &code).unwrap_err().text.clone(),
+            "'/old/' redirects to both '/a' and '/b'"
+        );
+    }
+
+    #[test]
+    fn redirects_stay_in_the_output_directory() {
+        for redirect_from in ["/../../tmp/x", "/old/..", "//old", "/old/./new"] {
+            assert_eq!(
+                redirect_error(redirect_from),
+                format!("invalid redirect path '{redirect_from}' for page 'Moved'")
+            );
+        }
+    }
+
+    #[test]
+    fn nav_blocks_are_declared_once() {
+        let code = format!("nav {{ page \"/\" }}\nnav {{ page \"/install\" }}\n{PAGES}");
+
+        assert_eq!(
+            parse_str("test.dl", &code).unwrap_err().text.clone(),
+            "duplicate nav definition"
+        );
+    }
+}
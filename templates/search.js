@@ -0,0 +1,187 @@
+// Offline search over the index DocsLegend writes to search-index.json. The index is only
+// fetched the first time the search box gets focus.
+(function () {
+    const input = document.getElementById("search_input");
+    const results = document.getElementById("search_results");
+
+    if (!input || !results) {
+        return;
+    }
+
+    const MAX_RESULTS = 10;
+    const SNIPPET_RADIUS = 60;
+    let index = null;
+    let loading = null;
+
+    function load() {
+        if (!loading) {
+            loading = fetch(input.dataset.index)
+                .then((response) => response.json())
+                .then((data) => {
+                    index = data;
+                });
+        }
+
+        return loading;
+    }
+
+    // must split text the same way the generator does
+    function tokenize(text) {
+        return text
+            .toLowerCase()
+            .split(/[^\p{L}\p{N}]+/u)
+            .filter((word) => [...word].length > 1);
+    }
+
+    // the position of the first term that is not smaller than prefix
+    function lowerBound(prefix) {
+        let low = 0;
+        let high = index.t.length;
+
+        while (low < high) {
+            const middle = (low + high) >> 1;
+
+            if (index.t[middle][0] < prefix) {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+
+        return low;
+    }
+
+    // scores of every document containing a term that starts with token, exact matches
+    // counting double
+    function scoreToken(token) {
+        const scores = new Map();
+
+        for (let i = lowerBound(token); i < index.t.length; i++) {
+            const [term, postings] = index.t[i];
+
+            if (!term.startsWith(token)) {
+                break;
+            }
+
+            const factor = term === token ? 2 : 1;
+
+            for (let j = 0; j < postings.length; j += 2) {
+                const document = postings[j];
+                scores.set(document, (scores.get(document) || 0) + postings[j + 1] * factor);
+            }
+        }
+
+        return scores;
+    }
+
+    function search(query) {
+        const tokens = tokenize(query);
+
+        if (tokens.length === 0) {
+            return { tokens, matches: [] };
+        }
+
+        let totals = null;
+
+        for (const token of tokens) {
+            const scores = scoreToken(token);
+
+            if (totals === null) {
+                totals = scores;
+                continue;
+            }
+
+            // every token has to match
+            for (const [document, score] of totals) {
+                if (scores.has(document)) {
+                    totals.set(document, score + scores.get(document));
+                } else {
+                    totals.delete(document);
+                }
+            }
+        }
+
+        const matches = [...totals]
+            .sort((a, b) => b[1] - a[1])
+            .slice(0, MAX_RESULTS)
+            .map(([document]) => index.d[document]);
+
+        return { tokens, matches };
+    }
+
+    function escapeHtml(text) {
+        return text
+            .replace(/&/g, "&amp;")
+            .replace(/</g, "&lt;")
+            .replace(/>/g, "&gt;")
+            .replace(/"/g, "&quot;");
+    }
+
+    function highlight(text, tokens) {
+        const escaped = tokens.map((token) => token.replace(/[.*+?^${}()|[\]\\]/g, "\\$&"));
+        const pattern = new RegExp("(" + escaped.join("|") + ")", "giu");
+
+        return text
+            .split(pattern)
+            .map((part, i) => (i % 2 === 1 ? "<mark>" + escapeHtml(part) + "</mark>" : escapeHtml(part)))
+            .join("");
+    }
+
+    function snippet(text, tokens) {
+        const lower = text.toLowerCase();
+        let first = -1;
+
+        for (const token of tokens) {
+            const position = lower.indexOf(token);
+
+            if (position !== -1 && (first === -1 || position < first)) {
+                first = position;
+            }
+        }
+
+        const start = Math.max(0, first - SNIPPET_RADIUS);
+        const end = Math.min(text.length, Math.max(first, 0) + SNIPPET_RADIUS * 2);
+        const prefix = start > 0 ? "…" : "";
+        const suffix = end < text.length ? "…" : "";
+
+        return prefix + highlight(text.slice(start, end), tokens) + suffix;
+    }
+
+    function show(query) {
+        const { tokens, matches } = search(query);
+
+        if (tokens.length === 0) {
+            results.innerHTML = "";
+            return;
+        }
+
+        if (matches.length === 0) {
+            results.innerHTML = '<li class="search_empty">No results</li>';
+            return;
+        }
+
+        results.innerHTML = matches
+            .map(
+                (document) =>
+                    '<li><a href="' +
+                    escapeHtml(document.u) +
+                    '"><span class="search_title">' +
+                    highlight(document.t, tokens) +
+                    '</span><span class="search_snippet">' +
+                    snippet(document.x, tokens) +
+                    "</span></a></li>",
+            )
+            .join("");
+    }
+
+    input.addEventListener("focus", load);
+    input.addEventListener("input", () => {
+        load().then(() => show(input.value));
+    });
+    input.addEventListener("keydown", (event) => {
+        if (event.key === "Escape") {
+            input.value = "";
+            results.innerHTML = "";
+        }
+    });
+})();
@@ -0,0 +1,117 @@
+// Turns the stacked tab panels DocsLegend renders into tab widgets. Groups that share a key
+// switch together, and the choice is remembered for the next page. Without this script
+// every tab stays visible under its own heading.
+(function () {
+    const STORAGE_PREFIX = "dl-tabs:";
+    const groups = Array.from(document.querySelectorAll(".tabs"));
+
+    function remembered(key) {
+        try {
+            return localStorage.getItem(STORAGE_PREFIX + key);
+        } catch {
+            return null;
+        }
+    }
+
+    function remember(key, name) {
+        try {
+            localStorage.setItem(STORAGE_PREFIX + key, name);
+        } catch {
+            // storage can be turned off, the choice then only lasts for this page
+        }
+    }
+
+    function tabsOf(group) {
+        return Array.from(group.querySelectorAll(':scope > [role="tablist"] > [role="tab"]'));
+    }
+
+    function hasTab(group, name) {
+        return tabsOf(group).some((tab) => tab.dataset.tab === name);
+    }
+
+    function select(group, name) {
+        for (const tab of tabsOf(group)) {
+            const selected = tab.dataset.tab === name;
+
+            tab.setAttribute("aria-selected", String(selected));
+            tab.tabIndex = selected ? 0 : -1;
+            document.getElementById(tab.getAttribute("aria-controls")).hidden = !selected;
+        }
+    }
+
+    function choose(group, name) {
+        const key = group.dataset.tabKey;
+
+        if (!key) {
+            select(group, name);
+            return;
+        }
+
+        for (const other of groups) {
+            if (other.dataset.tabKey === key && hasTab(other, name)) {
+                select(other, name);
+            }
+        }
+
+        remember(key, name);
+    }
+
+    function onKeydown(event, group) {
+        const tabs = tabsOf(group);
+        const current = tabs.indexOf(event.currentTarget);
+        const next = {
+            ArrowRight: (current + 1) % tabs.length,
+            ArrowLeft: (current - 1 + tabs.length) % tabs.length,
+            Home: 0,
+            End: tabs.length - 1,
+        }[event.key];
+
+        if (next === undefined) {
+            return;
+        }
+
+        event.preventDefault();
+        choose(group, tabs[next].dataset.tab);
+        tabs[next].focus();
+    }
+
+    for (const group of groups) {
+        const panels = Array.from(group.querySelectorAll(":scope > .tab-panel"));
+        const list = document.createElement("div");
+
+        if (panels.length === 0) {
+            continue;
+        }
+
+        list.setAttribute("role", "tablist");
+
+        for (const panel of panels) {
+            const title = panel.querySelector(":scope > .tab-title");
+            const tab = document.createElement("button");
+
+            tab.type = "button";
+            tab.id = panel.id + "-tab";
+            tab.dataset.tab = panel.dataset.tab;
+            tab.textContent = title ? title.textContent : panel.dataset.tab;
+            tab.setAttribute("role", "tab");
+            tab.setAttribute("aria-controls", panel.id);
+            tab.addEventListener("click", () => choose(group, panel.dataset.tab));
+            tab.addEventListener("keydown", (event) => onKeydown(event, group));
+            list.appendChild(tab);
+
+            panel.setAttribute("role", "tabpanel");
+            panel.setAttribute("aria-labelledby", tab.id);
+            panel.tabIndex = 0;
+
+            if (title) {
+                title.hidden = true;
+            }
+        }
+
+        group.insertBefore(list, group.firstChild);
+
+        const stored = group.dataset.tabKey && remembered(group.dataset.tabKey);
+
+        select(group, stored && hasTab(group, stored) ? stored : panels[0].dataset.tab);
+    }
+})();